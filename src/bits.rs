@@ -0,0 +1,135 @@
+// Helpers for GF(2) vectors packed into `u64` words.
+//
+// Bit `j` of a packed row lives in word `j / WORD_BITS` at position `j % WORD_BITS`
+// (least significant bit first). Bits past the logical length are always kept at zero,
+// so word-wise operations (XOR, popcount, pivot search) never need to mask the tail.
+
+pub const WORD_BITS: usize = 64;
+
+pub fn words_for(nbits: usize) -> usize {
+    nbits.div_ceil(WORD_BITS)
+}
+
+pub fn get_bit(row: &[u64], j: usize) -> u8 {
+    ((row[j / WORD_BITS] >> (j % WORD_BITS)) & 1) as u8
+}
+
+pub fn set_bit(row: &mut [u64], j: usize, value: u8) {
+    let mask = 1u64 << (j % WORD_BITS);
+    if value & 1 == 1 {
+        row[j / WORD_BITS] |= mask;
+    } else {
+        row[j / WORD_BITS] &= !mask;
+    }
+}
+
+pub fn xor_into(dst: &mut [u64], src: &[u64]) {
+    for (d, s) in dst.iter_mut().zip(src.iter()) {
+        *d ^= *s;
+    }
+}
+
+pub fn is_zero(row: &[u64]) -> bool {
+    row.iter().all(|&w| w == 0)
+}
+
+/// Index of the first set bit, i.e. the pivot column of a row.
+pub fn first_one(row: &[u64]) -> Option<usize> {
+    row.iter()
+        .position(|&w| w != 0)
+        .map(|i| i * WORD_BITS + row[i].trailing_zeros() as usize)
+}
+
+pub fn popcount(row: &[u64]) -> usize {
+    row.iter().map(|w| w.count_ones() as usize).sum()
+}
+
+/// Inner product over GF(2).
+pub fn dot(a: &[u64], b: &[u64]) -> u8 {
+    let ones: u32 = a.iter().zip(b.iter()).map(|(x, y)| (x & y).count_ones()).sum();
+    (ones & 1) as u8
+}
+
+/// Packs a 0/1 byte vector into `words_for(nbits)` words, ignoring entries past `nbits`.
+pub fn pack(bits: &[u8], nbits: usize) -> Vec<u64> {
+    let mut row = vec![0u64; words_for(nbits)];
+    for (j, &b) in bits.iter().take(nbits).enumerate() {
+        if b & 1 == 1 {
+            row[j / WORD_BITS] |= 1u64 << (j % WORD_BITS);
+        }
+    }
+    row
+}
+
+pub fn unpack(row: &[u64], nbits: usize) -> Vec<u8> {
+    (0..nbits).map(|j| get_bit(row, j)).collect()
+}
+
+/// Reads `count <= WORD_BITS` bits starting at bit `start` as an integer (bit `start` lowest).
+pub fn read_bits(row: &[u64], start: usize, count: usize) -> u64 {
+    let word = start / WORD_BITS;
+    let shift = start % WORD_BITS;
+    let mut value = row[word] >> shift;
+    if shift != 0 && shift + count > WORD_BITS {
+        value |= row[word + 1] << (WORD_BITS - shift);
+    }
+    if count < WORD_BITS {
+        value &= (1u64 << count) - 1;
+    }
+    value
+}
+
+/// Index of the first set bit at or after bit `start`.
+pub fn first_one_from(row: &[u64], start: usize) -> Option<usize> {
+    let word = start / WORD_BITS;
+    if word >= row.len() {
+        return None;
+    }
+    let head = row[word] & (u64::MAX << (start % WORD_BITS));
+    if head != 0 {
+        return Some(word * WORD_BITS + head.trailing_zeros() as usize);
+    }
+    first_one(&row[word + 1..]).map(|j| (word + 1) * WORD_BITS + j)
+}
+
+/// XORs `src` into `dst` on bits `start..` only.
+pub fn xor_from(dst: &mut [u64], src: &[u64], start: usize) {
+    let word = start / WORD_BITS;
+    if word >= dst.len() {
+        return;
+    }
+    dst[word] ^= src[word] & (u64::MAX << (start % WORD_BITS));
+    xor_into(&mut dst[word + 1..], &src[word + 1..]);
+}
+
+/// Copy of the first `nbits` bits of `row`, packed into `words_for(nbits)` words.
+pub fn truncate(row: &[u64], nbits: usize) -> Vec<u64> {
+    let mut out: Vec<u64> = row.iter().take(words_for(nbits)).copied().collect();
+    out.resize(words_for(nbits), 0);
+    if !nbits.is_multiple_of(WORD_BITS) {
+        let last = out.len() - 1;
+        out[last] &= (1u64 << (nbits % WORD_BITS)) - 1;
+    }
+    out
+}
+
+/// Bits `start..start + len` of `row`, repacked from bit 0.
+pub fn extract(row: &[u64], start: usize, len: usize) -> Vec<u64> {
+    (0..words_for(len))
+        .map(|w| read_bits(row, start + w * WORD_BITS, WORD_BITS.min(len - w * WORD_BITS)))
+        .collect()
+}
+
+/// ORs the first `len` bits of `src` into `dst` starting at bit `start`.
+pub fn or_at(dst: &mut [u64], start: usize, src: &[u64], len: usize) {
+    for (w, &word) in src.iter().enumerate().take(words_for(len)) {
+        let count = WORD_BITS.min(len - w * WORD_BITS);
+        let value = if count < WORD_BITS { word & ((1u64 << count) - 1) } else { word };
+        let pos = start + w * WORD_BITS;
+        let shift = pos % WORD_BITS;
+        dst[pos / WORD_BITS] |= value << shift;
+        if shift != 0 && shift + count > WORD_BITS {
+            dst[pos / WORD_BITS + 1] |= value >> (WORD_BITS - shift);
+        }
+    }
+}
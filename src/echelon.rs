@@ -0,0 +1,137 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyIterator, PyTuple};
+use crate::bits;
+use crate::error::Error;
+use crate::matrix::{Matrix, RowOps};
+use crate::oplog::OpLog;
+#[cfg(feature = "serde")]
+use crate::serialize;
+
+/// Result of an echelon routine: the reduced matrix, the operations applied and, when
+/// requested, the transformation, together with the pivot structure.
+///
+/// It still unpacks like the tuples returned before, `(reduced, operations)` or
+/// `(reduced, operations, transform)`.
+#[pyclass]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EchelonForm {
+    #[pyo3(get)]
+    pub reduced: Matrix,
+    #[pyo3(get)]
+    pub operations: OpLog,
+    /// `U` with `U * A` equal to `reduced` (`A * U` for a column echelon form), if tracked.
+    #[pyo3(get)]
+    pub transform: Option<Matrix>,
+    /// Pivot columns, one per nonzero row; pivot rows for a column echelon form.
+    #[pyo3(get)]
+    pub pivots: Vec<usize>,
+    /// Columns without a pivot; the zero columns for a column echelon form.
+    #[pyo3(get)]
+    pub free_columns: Vec<usize>,
+}
+
+#[pymethods]
+impl EchelonForm {
+    #[getter]
+    fn rank(&self) -> usize {
+        self.pivots.len()
+    }
+
+    fn __len__(&self) -> usize {
+        if self.transform.is_some() { 3 } else { 2 }
+    }
+
+    fn __getitem__(&self, py: Python<'_>, index: isize) -> PyResult<PyObject> {
+        let fields = self.fields(py)?;
+        let len = fields.len();
+        let i = if index < 0 { index + len as isize } else { index };
+        if i < 0 || i >= len as isize {
+            return Err(Error::Index { index, len }.into());
+        }
+        Ok(fields.get_item(i as usize)?.unbind())
+    }
+
+    fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyIterator>> {
+        PyIterator::from_object(self.fields(py)?.as_any())
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "EchelonForm(rank={}, pivots={:?}, free_columns={:?})",
+            self.rank(),
+            self.pivots,
+            self.free_columns
+        )
+    }
+
+    /// JSON object with every field, matrices encoded as by `Matrix.to_json`.
+    #[cfg(feature = "serde")]
+    fn to_json(&self) -> PyResult<String> {
+        serialize::to_json(self)
+    }
+
+    #[cfg(feature = "serde")]
+    #[staticmethod]
+    fn from_json(text: &str) -> PyResult<EchelonForm> {
+        serialize::from_json(text)
+    }
+
+    /// Compact CBOR encoding, matrices encoded as by `Matrix.to_cbor`.
+    #[cfg(feature = "serde")]
+    fn to_cbor<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyBytes>> {
+        serialize::to_cbor(py, self)
+    }
+
+    #[cfg(feature = "serde")]
+    #[staticmethod]
+    fn from_cbor(data: &[u8]) -> PyResult<EchelonForm> {
+        serialize::from_cbor(data)
+    }
+}
+
+impl EchelonForm {
+    /// Wraps a row echelon form, reading the pivots off the leading ones of its rows.
+    pub(crate) fn rows(reduced: Matrix, operations: RowOps) -> Self {
+        let pivots = reduced.pivot_columns();
+        let mut is_pivot = vec![false; reduced.ncols()];
+        for &p in &pivots {
+            is_pivot[p] = true;
+        }
+        let free_columns = (0..reduced.ncols()).filter(|&j| !is_pivot[j]).collect();
+        EchelonForm {
+            reduced,
+            operations: operations.operations,
+            transform: operations.transform,
+            pivots,
+            free_columns,
+        }
+    }
+
+    /// Wraps the transpose of a row echelon form of `A^T`, which is a column echelon
+    /// form of `A`.
+    pub(crate) fn columns(reduced_transpose: Matrix, operations: RowOps) -> Self {
+        let pivots = reduced_transpose.pivot_columns();
+        let free_columns = (0..reduced_transpose.nrows())
+            .filter(|&i| bits::is_zero(&reduced_transpose.rows[i]))
+            .collect();
+        EchelonForm {
+            reduced: reduced_transpose.transposed(),
+            operations: operations.operations,
+            transform: operations.transform.map(|u| u.transposed()),
+            pivots,
+            free_columns,
+        }
+    }
+
+    fn fields<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyTuple>> {
+        let reduced = self.reduced.clone().into_pyobject(py)?.into_any();
+        let operations = self.operations.clone().into_pyobject(py)?.into_any();
+        match &self.transform {
+            Some(transform) => {
+                PyTuple::new(py, [reduced, operations, transform.clone().into_pyobject(py)?.into_any()])
+            }
+            None => PyTuple::new(py, [reduced, operations]),
+        }
+    }
+}
@@ -0,0 +1,104 @@
+// Errors raised on malformed input, and the Python exceptions they map to.
+//
+// `DimensionError` and `NonBinaryValueError` derive from `ValueError`, so code catching
+// `ValueError` keeps working; bad indices raise the built-in `IndexError`.
+use std::fmt;
+use pyo3::create_exception;
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::PyErr;
+
+create_exception!(
+    algebraic_immunity_utils,
+    DimensionError,
+    PyValueError,
+    "Operands whose shapes do not fit together."
+);
+create_exception!(
+    algebraic_immunity_utils,
+    NonBinaryValueError,
+    PyValueError,
+    "An entry other than 0 or 1, or a string of characters other than '0' and '1'."
+);
+
+#[derive(Debug)]
+pub enum Error {
+    /// Shapes that do not fit together, described by the message.
+    Dimension(String),
+    /// An entry other than 0 or 1.
+    NonBinaryValue(i64),
+    /// A string expected to hold only '0' and '1'.
+    NonBinaryString(String),
+    /// `index` on an axis of length `len`.
+    Index { index: isize, len: usize },
+    /// The range `start..end` on an axis of length `len`.
+    Range { start: usize, end: usize, len: usize },
+}
+
+impl Error {
+    pub fn dimension(message: impl Into<String>) -> Self {
+        Error::Dimension(message.into())
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Dimension(message) => f.write_str(message),
+            Error::NonBinaryValue(value) => write!(f, "entries must be 0 or 1, got {}", value),
+            Error::NonBinaryString(s) => write!(f, "{:?} is not a string of 0s and 1s", s),
+            Error::Index { index, len } => write!(f, "index {} out of range for length {}", index, len),
+            Error::Range { start, end, len } => {
+                write!(f, "range {}..{} out of bounds for length {}", start, end, len)
+            }
+        }
+    }
+}
+
+impl From<Error> for PyErr {
+    fn from(err: Error) -> PyErr {
+        let message = err.to_string();
+        match err {
+            Error::Dimension(_) => DimensionError::new_err(message),
+            Error::NonBinaryValue(_) | Error::NonBinaryString(_) => NonBinaryValueError::new_err(message),
+            Error::Index { .. } | Error::Range { .. } => PyIndexError::new_err(message),
+        }
+    }
+}
+
+/// Checks that `index` is a valid index on an axis of length `len`.
+pub fn check_index(index: usize, len: usize) -> Result<(), Error> {
+    if index >= len {
+        return Err(Error::Index { index: index as isize, len });
+    }
+    Ok(())
+}
+
+/// Checks that every entry is 0 or 1.
+pub fn check_binary<T: Copy + Into<i64>>(values: &[T]) -> Result<(), Error> {
+    match values.iter().map(|&v| v.into()).find(|&v| v != 0 && v != 1) {
+        Some(value) => Err(Error::NonBinaryValue(value)),
+        None => Ok(()),
+    }
+}
+
+/// Checks that the strings only hold '0' and '1' and all have the same length.
+pub fn check_binary_strings<'a>(strings: impl IntoIterator<Item = &'a String>) -> Result<(), Error> {
+    let mut width = None;
+    for s in strings {
+        if !s.bytes().all(|c| c == b'0' || c == b'1') {
+            return Err(Error::NonBinaryString(s.clone()));
+        }
+        match width {
+            Some(width) if width != s.len() => {
+                return Err(Error::dimension(format!(
+                    "{:?} has length {}, expected {}",
+                    s,
+                    s.len(),
+                    width
+                )))
+            }
+            _ => width = Some(s.len()),
+        }
+    }
+    Ok(())
+}
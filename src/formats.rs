@@ -0,0 +1,430 @@
+// Text formats shared with other tools, and images for looking at large matrices.
+//
+// - `matrixmarket`: the MatrixMarket exchange format read by SciPy, Sage and most sparse
+//   solvers. Matrices are written in coordinate form, or column by column in array form
+//   with `matrixmarket-array`. Both forms are read, including symmetric files that store
+//   one triangle.
+// - `m4ri`: the plain text Sage and M4RI print, one bracketed row per line such as
+//   `[1 0 1]`. Entries may also be separated by commas, `|` or `:`, or not at all, and `.`
+//   stands for 0.
+// - `magma`: a `Matrix(GF(2), nrows, ncols, [...])` literal. The forms with one dimension
+//   or a list of rows are read too.
+// - `gf2`: the bit-packed binary files of `MappedMatrix` and `main.rs`, read in full.
+// - `pbm` and `pgm`: binary images with one black pixel per one. These are write-only. A
+//   PGM pixel can cover a square block of entries, and its grey level then shows the
+//   density of ones in the block.
+//
+// Integer entries of MatrixMarket and Magma files are reduced modulo 2, as those tools do
+// when coercing into GF(2).
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use crate::bits;
+use crate::error::Error;
+use crate::mapped;
+use crate::matrix::Matrix;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    MatrixMarket,
+    MatrixMarketArray,
+    M4ri,
+    Magma,
+    Packed,
+    Pbm,
+    Pgm,
+}
+
+impl Format {
+    /// The format named `format`, or the one matching the extension of `path`.
+    pub fn parse(format: Option<&str>, path: &Path) -> PyResult<Self> {
+        let name = match format {
+            Some(format) => format.to_string(),
+            None => path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .map(|extension| extension.to_ascii_lowercase())
+                .ok_or_else(|| {
+                    PyValueError::new_err(format!("cannot infer the format of {:?}, pass format=", path))
+                })?,
+        };
+        match name.as_str() {
+            "matrixmarket" | "mtx" => Ok(Format::MatrixMarket),
+            "matrixmarket-array" => Ok(Format::MatrixMarketArray),
+            "m4ri" | "sage" | "txt" => Ok(Format::M4ri),
+            "magma" | "m" | "mag" => Ok(Format::Magma),
+            "gf2" | "bin" => Ok(Format::Packed),
+            "pbm" => Ok(Format::Pbm),
+            "pgm" => Ok(Format::Pgm),
+            _ => Err(PyValueError::new_err(format!(
+                "format must be 'matrixmarket', 'matrixmarket-array', 'm4ri', 'magma', 'gf2', 'pbm' or 'pgm', got {:?}",
+                name
+            ))),
+        }
+    }
+}
+
+/// Writes `m` to `path`. Each PGM pixel covers `block x block` entries.
+pub fn save(m: &Matrix, path: &Path, format: Format, block: usize) -> PyResult<()> {
+    let data = match format {
+        Format::MatrixMarket => write_coordinate(m).into_bytes(),
+        Format::MatrixMarketArray => write_array(m).into_bytes(),
+        Format::M4ri => write_m4ri(m).into_bytes(),
+        Format::Magma => write_magma(m).into_bytes(),
+        Format::Packed => return mapped::write_matrix(m, path),
+        Format::Pbm => write_pbm(m),
+        Format::Pgm => write_pgm(m, block),
+    };
+    fs::write(path, data)?;
+    Ok(())
+}
+
+/// Reads a matrix from `path`.
+pub fn load(path: &Path, format: Format) -> PyResult<Matrix> {
+    match format {
+        Format::Packed => return mapped::read_matrix(path),
+        Format::Pbm | Format::Pgm => return Err(PyValueError::new_err("PBM and PGM images can only be written")),
+        _ => {}
+    }
+    let data = fs::read(path)?;
+    let text = std::str::from_utf8(&data).map_err(|_| malformed("the file is not UTF-8 text"))?;
+    match format {
+        Format::M4ri => read_m4ri(text),
+        Format::Magma => read_magma(text),
+        Format::MatrixMarket | Format::MatrixMarketArray => read_matrix_market(text),
+        Format::Packed | Format::Pbm | Format::Pgm => unreachable!(),
+    }
+}
+
+fn malformed(message: impl Into<String>) -> PyErr {
+    PyValueError::new_err(message.into())
+}
+
+// A zero matrix of the shape declared by a file, raising instead of aborting when a few
+// bytes of header ask for more memory than can be allocated.
+fn zeros(nrows: usize, ncols: usize) -> PyResult<Matrix> {
+    // Each row also takes the three words of its `Vec`.
+    let words = (bits::words_for(ncols) + 3).checked_mul(nrows);
+    if words.is_none_or(|words| Vec::<u64>::new().try_reserve_exact(words).is_err()) {
+        return Err(Error::dimension(format!("a {}x{} matrix is too large to allocate", nrows, ncols)).into());
+    }
+    Ok(Matrix::zeros(nrows, ncols))
+}
+
+fn ones(row: &[u64]) -> impl Iterator<Item = usize> + '_ {
+    std::iter::successors(bits::first_one(row), |&j| bits::first_one_from(row, j + 1))
+}
+
+fn write_coordinate(m: &Matrix) -> String {
+    let nnz: usize = m.rows.iter().map(bits::popcount).sum();
+    let mut out = String::from("%%MatrixMarket matrix coordinate integer general\n");
+    writeln!(out, "{} {} {}", m.nrows(), m.ncols(), nnz).unwrap();
+    for (i, row) in m.rows.iter().enumerate() {
+        for j in ones(row) {
+            writeln!(out, "{} {} 1", i + 1, j + 1).unwrap();
+        }
+    }
+    out
+}
+
+fn write_array(m: &Matrix) -> String {
+    let mut out = String::from("%%MatrixMarket matrix array integer general\n");
+    writeln!(out, "{} {}", m.nrows(), m.ncols()).unwrap();
+    for j in 0..m.ncols() {
+        for row in &m.rows {
+            writeln!(out, "{}", bits::get_bit(row, j)).unwrap();
+        }
+    }
+    out
+}
+
+fn row_entries(row: &[u64], ncols: usize, separator: &str) -> String {
+    (0..ncols)
+        .map(|j| bits::get_bit(row, j).to_string())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn write_m4ri(m: &Matrix) -> String {
+    m.rows
+        .iter()
+        .map(|row| format!("[{}]\n", row_entries(row, m.ncols(), " ")))
+        .collect()
+}
+
+fn write_magma(m: &Matrix) -> String {
+    if m.nrows() == 0 || m.ncols() == 0 {
+        return format!("Matrix(GF(2), {}, {}, [])\n", m.nrows(), m.ncols());
+    }
+    let rows: Vec<String> = m
+        .rows
+        .iter()
+        .map(|row| format!("    {}", row_entries(row, m.ncols(), ", ")))
+        .collect();
+    format!("Matrix(GF(2), {}, {}, [\n{}\n])\n", m.nrows(), m.ncols(), rows.join(",\n"))
+}
+
+fn write_pbm(m: &Matrix) -> Vec<u8> {
+    let mut out = format!("P4\n{} {}\n", m.ncols(), m.nrows()).into_bytes();
+    let width = m.ncols().div_ceil(8);
+    for row in &m.rows {
+        let bytes = row.iter().flat_map(|word| word.to_le_bytes());
+        out.extend(bytes.take(width).map(u8::reverse_bits));
+    }
+    out
+}
+
+// Number of ones among the `len` bits of `row` from `start`.
+fn count_ones(row: &[u64], start: usize, len: usize) -> usize {
+    (start..start + len)
+        .step_by(bits::WORD_BITS)
+        .map(|s| bits::read_bits(row, s, (start + len - s).min(bits::WORD_BITS)).count_ones() as usize)
+        .sum()
+}
+
+fn write_pgm(m: &Matrix, block: usize) -> Vec<u8> {
+    let (height, width) = (m.nrows().div_ceil(block), m.ncols().div_ceil(block));
+    let mut out = format!("P5\n{} {}\n255\n", width, height).into_bytes();
+    for ti in 0..height {
+        let rows: Vec<&[u64]> = m.rows.iter().skip(ti * block).take(block).collect();
+        for tj in 0..width {
+            let start = tj * block;
+            let len = block.min(m.ncols() - start);
+            let ones: usize = rows.iter().map(|row| count_ones(row, start, len)).sum();
+            let cells = rows.len() * len;
+            out.push((255 - (255 * ones + cells / 2) / cells) as u8);
+        }
+    }
+    out
+}
+
+fn parse_integer(token: &str) -> PyResult<i64> {
+    token
+        .parse()
+        .map_err(|_| malformed(format!("expected an integer, got {:?}", token)))
+}
+
+fn next_integer<'a>(tokens: &mut impl Iterator<Item = &'a str>, what: &str) -> PyResult<i64> {
+    let token = tokens
+        .next()
+        .ok_or_else(|| malformed(format!("unexpected end of file, expected {}", what)))?;
+    parse_integer(token)
+}
+
+fn next_index<'a>(tokens: &mut impl Iterator<Item = &'a str>, what: &str) -> PyResult<usize> {
+    let value = next_integer(tokens, what)?;
+    usize::try_from(value).map_err(|_| malformed(format!("{} must not be negative, got {}", what, value)))
+}
+
+fn read_matrix_market(text: &str) -> PyResult<Matrix> {
+    let mut lines = text.lines();
+    let header = lines.next().unwrap_or("").to_ascii_lowercase();
+    let fields: Vec<&str> = header.split_whitespace().collect();
+    let ["%%matrixmarket", "matrix", layout, field, symmetry] = fields[..] else {
+        return Err(malformed("missing '%%MatrixMarket matrix' header"));
+    };
+    let pattern = match field {
+        "integer" => false,
+        "pattern" if layout == "coordinate" => true,
+        _ => return Err(malformed(format!("unsupported MatrixMarket field {:?}", field))),
+    };
+    let (symmetric, skew) = match symmetry {
+        "general" => (false, false),
+        "symmetric" => (true, false),
+        "skew-symmetric" => (true, true),
+        _ => return Err(malformed(format!("unsupported MatrixMarket symmetry {:?}", symmetry))),
+    };
+    let mut tokens = lines
+        .filter(|line| !line.trim_start().starts_with('%'))
+        .flat_map(str::split_whitespace);
+
+    let nrows = next_index(&mut tokens, "the number of rows")?;
+    let ncols = next_index(&mut tokens, "the number of columns")?;
+    if symmetric && nrows != ncols {
+        return Err(Error::dimension(format!("a {} matrix must be square, got {}x{}", symmetry, nrows, ncols)).into());
+    }
+    let mut m = zeros(nrows, ncols)?;
+    // Duplicate coordinates add up, as they do in SciPy.
+    let flip = |m: &mut Matrix, i: usize, j: usize| {
+        m.rows[i][j / bits::WORD_BITS] ^= 1 << (j % bits::WORD_BITS);
+        if symmetric && i != j {
+            m.rows[j][i / bits::WORD_BITS] ^= 1 << (i % bits::WORD_BITS);
+        }
+    };
+    match layout {
+        "coordinate" => {
+            let nnz = next_index(&mut tokens, "the number of entries")?;
+            for _ in 0..nnz {
+                let i = next_index(&mut tokens, "a row index")?;
+                let j = next_index(&mut tokens, "a column index")?;
+                if !(1..=nrows).contains(&i) || !(1..=ncols).contains(&j) {
+                    return Err(malformed(format!("entry ({}, {}) outside a {}x{} matrix", i, j, nrows, ncols)));
+                }
+                if pattern || next_integer(&mut tokens, "a value")?.rem_euclid(2) == 1 {
+                    flip(&mut m, i - 1, j - 1);
+                }
+            }
+        }
+        "array" => {
+            for j in 0..ncols {
+                let first = if skew { j + 1 } else if symmetric { j } else { 0 };
+                for i in first..nrows {
+                    if next_integer(&mut tokens, "a value")?.rem_euclid(2) == 1 {
+                        flip(&mut m, i, j);
+                    }
+                }
+            }
+        }
+        _ => return Err(malformed(format!("unsupported MatrixMarket layout {:?}", layout))),
+    }
+    if let Some(token) = tokens.next() {
+        return Err(malformed(format!("unexpected {:?} after the last entry", token)));
+    }
+    Ok(m)
+}
+
+fn read_m4ri(text: &str) -> PyResult<Matrix> {
+    let mut rows: Vec<Vec<u8>> = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let inner = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')).unwrap_or(line);
+        let mut row = Vec::new();
+        for c in inner.chars().filter(|&c| !(c.is_whitespace() || matches!(c, ',' | '|' | ':'))) {
+            match c {
+                '0' | '.' => row.push(0),
+                '1' => row.push(1),
+                _ => return Err(malformed(format!("line {}: unexpected {:?}", n + 1, c))),
+            }
+        }
+        if let Some(first) = rows.first() {
+            if first.len() != row.len() {
+                return Err(Error::dimension(format!(
+                    "line {} has {} entries, expected {}",
+                    n + 1,
+                    row.len(),
+                    first.len()
+                ))
+                .into());
+            }
+        }
+        rows.push(row);
+    }
+    Ok(Matrix::new(rows))
+}
+
+enum Token {
+    Open,
+    Close,
+    Integer(i64),
+}
+
+fn tokenize(text: &str) -> PyResult<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '[' => tokens.push(Token::Open),
+            ']' => tokens.push(Token::Close),
+            ',' => {}
+            c if c.is_whitespace() => {}
+            '-' | '0'..='9' => {
+                let mut end = start + 1;
+                while let Some(&(i, d)) = chars.peek().filter(|(_, d)| d.is_ascii_digit()) {
+                    end = i + d.len_utf8();
+                    chars.next();
+                }
+                tokens.push(Token::Integer(parse_integer(&text[start..end])?));
+            }
+            _ => return Err(malformed(format!("unexpected {:?} in a Magma matrix", c))),
+        }
+    }
+    Ok(tokens)
+}
+
+fn read_magma(text: &str) -> PyResult<Matrix> {
+    let text = text.trim();
+    let text = text.strip_suffix(';').unwrap_or(text).trim_end();
+    let arguments = text
+        .find("Matrix(")
+        .map(|start| &text[start + "Matrix(".len()..])
+        .and_then(|rest| rest.strip_suffix(')'))
+        .ok_or_else(|| malformed("expected a Matrix(GF(2), ...) literal"))?;
+    let entries = arguments
+        .trim_start()
+        .strip_prefix("GF(2)")
+        .and_then(|rest| rest.trim_start().strip_prefix(','))
+        .ok_or_else(|| malformed("expected a matrix over GF(2)"))?;
+    let tokens = tokenize(entries)?;
+
+    let dims: Vec<usize> = tokens
+        .iter()
+        .map_while(|token| match token {
+            Token::Integer(d) => Some(usize::try_from(*d).unwrap_or(usize::MAX)),
+            _ => None,
+        })
+        .collect();
+    let list = &tokens[dims.len()..];
+    if dims.len() > 2 || !matches!(list.first(), Some(Token::Open)) || !matches!(list.last(), Some(Token::Close)) {
+        return Err(malformed("expected at most two dimensions followed by a list of entries"));
+    }
+    let list = &list[1..list.len() - 1];
+
+    let mut rows: Vec<Vec<u8>> = Vec::new();
+    let mut flat: Vec<u8> = Vec::new();
+    let mut row: Option<Vec<u8>> = None;
+    for token in list {
+        match (token, &mut row) {
+            (Token::Open, None) => row = Some(Vec::new()),
+            (Token::Close, Some(_)) => rows.push(row.take().unwrap()),
+            (Token::Integer(v), Some(row)) => row.push(v.rem_euclid(2) as u8),
+            (Token::Integer(v), None) => flat.push(v.rem_euclid(2) as u8),
+            _ => return Err(malformed("unbalanced brackets in a Magma matrix")),
+        }
+    }
+    if row.is_some() || (!rows.is_empty() && !flat.is_empty()) {
+        return Err(malformed("expected either a flat list of entries or a list of rows"));
+    }
+
+    if rows.is_empty() {
+        let (nrows, ncols) = match dims[..] {
+            [] => (1, flat.len()),
+            [nrows] if nrows > 0 => (nrows, flat.len() / nrows),
+            [_] => (0, 0),
+            [nrows, ncols] => (nrows, ncols),
+            _ => unreachable!(),
+        };
+        if nrows.checked_mul(ncols) != Some(flat.len()) {
+            return Err(Error::dimension(format!(
+                "{} entries do not fill a {}x{} matrix",
+                flat.len(),
+                nrows,
+                ncols
+            ))
+            .into());
+        }
+        let mut m = zeros(nrows, ncols)?;
+        for (row, chunk) in m.rows.iter_mut().zip(flat.chunks(ncols.max(1))) {
+            row.copy_from_slice(&bits::pack(chunk, ncols));
+        }
+        return Ok(m);
+    }
+    let ncols = rows[0].len();
+    if rows.iter().any(|row| row.len() != ncols) {
+        return Err(Error::dimension("rows of a Magma matrix must have the same length").into());
+    }
+    if dims.first().is_some_and(|&nrows| nrows != rows.len()) || dims.get(1).is_some_and(|&n| n != ncols) {
+        return Err(Error::dimension(format!(
+            "a {}x{} list of rows does not match the dimensions {:?}",
+            rows.len(),
+            ncols,
+            dims
+        ))
+        .into());
+    }
+    Ok(Matrix::new(rows))
+}
@@ -0,0 +1,221 @@
+// Characteristic and minimal polynomials and the Frobenius normal form.
+//
+// Everything is built from Krylov sequences `v, Av, A^2 v, ...` of column vectors. Each
+// new power is reduced against the previous ones, and the reduction also records the
+// polynomial `p` with `p(A) v` equal to the reduced vector, so the first power that
+// reduces to zero yields the minimal polynomial of `v`.
+//
+// A Krylov subspace `W` is invariant, and `A` induces an operator on `V / W`. Splitting
+// off such subspaces one after the other multiplies up to the characteristic polynomial.
+// When `W` is generated by a vector whose minimal polynomial is that of `A`, `W` has an
+// invariant complement, so the invariant factors of `A` are the minimal polynomial
+// followed by those of the quotient. Such a vector is found without factoring: vectors
+// with minimal polynomials `f` and `g` are combined into one with minimal polynomial
+// `lcm(f, g)` through a coprime splitting of the lcm computed with gcds.
+use crate::bits;
+use crate::gf2x::{self, Poly};
+use crate::matrix::Matrix;
+
+// Semi-echelon basis: every vector has its own pivot and vanishes on the pivots of the
+// vectors before it, so reducing in order clears all the pivots.
+#[derive(Clone, Default)]
+struct Subspace {
+    pivots: Vec<usize>,
+    vectors: Vec<Vec<u64>>,
+}
+
+impl Subspace {
+    fn reduce(&self, v: &mut [u64]) {
+        for (&pivot, b) in self.pivots.iter().zip(&self.vectors) {
+            if bits::get_bit(v, pivot) == 1 {
+                bits::xor_into(v, b);
+            }
+        }
+    }
+
+    // Adds the vectors of `other` to the subspace.
+    fn extend(&mut self, other: Subspace) {
+        for mut v in other.vectors {
+            self.reduce(&mut v);
+            if let Some(pivot) = bits::first_one(&v) {
+                self.pivots.push(pivot);
+                self.vectors.push(v);
+            }
+        }
+    }
+}
+
+fn unit_vector(n: usize, i: usize) -> Vec<u64> {
+    let mut v = vec![0u64; bits::words_for(n)];
+    bits::set_bit(&mut v, i, 1);
+    v
+}
+
+// Basis of the Krylov subspace of `v` and the minimal polynomial of `v`.
+fn krylov(a: &Matrix, v: &[u64]) -> (Subspace, Poly) {
+    let mut span = Subspace::default();
+    let mut polys: Vec<Poly> = Vec::new();
+    let mut power = v.to_vec();
+    for k in 0..=a.nrows() {
+        let mut r = power.clone();
+        let mut p = gf2x::monomial(k);
+        for (i, (&pivot, b)) in span.pivots.iter().zip(&span.vectors).enumerate() {
+            if bits::get_bit(&r, pivot) == 1 {
+                bits::xor_into(&mut r, b);
+                gf2x::add_assign(&mut p, &polys[i]);
+            }
+        }
+        let Some(pivot) = bits::first_one(&r) else {
+            return (span, p);
+        };
+        span.pivots.push(pivot);
+        span.vectors.push(r);
+        polys.push(p);
+        power = a.mul_packed_vec(&power);
+    }
+    unreachable!("a Krylov sequence in dimension n is dependent after n steps")
+}
+
+/// Minimal polynomial of the vector `v` under `a`.
+pub(crate) fn vector_minpoly(a: &Matrix, v: &[u64]) -> Poly {
+    krylov(a, v).1
+}
+
+// The operator induced by `a` on `V / w`, in the coordinates of the non-pivot columns.
+fn quotient(a: &Matrix, w: &Subspace) -> Matrix {
+    let n = a.nrows();
+    let mut is_pivot = vec![false; n];
+    for &p in &w.pivots {
+        is_pivot[p] = true;
+    }
+    let free: Vec<usize> = (0..n).filter(|&j| !is_pivot[j]).collect();
+    let columns = a.transposed();
+    let rows = free
+        .iter()
+        .map(|&q| {
+            let mut column = columns.rows[q].to_vec();
+            w.reduce(&mut column);
+            let mut projected = vec![0u64; bits::words_for(free.len())];
+            for (k, &j) in free.iter().enumerate() {
+                bits::set_bit(&mut projected, k, bits::get_bit(&column, j));
+            }
+            projected
+        });
+    Matrix::from_rows(rows, free.len()).transposed()
+}
+
+/// `p(A) v`, by Horner's rule.
+pub(crate) fn apply(a: &Matrix, p: &[u64], v: &[u64]) -> Vec<u64> {
+    let mut result = vec![0u64; v.len()];
+    let Some(degree) = gf2x::degree(p) else {
+        return result;
+    };
+    for i in (0..=degree).rev() {
+        result = a.mul_packed_vec(&result);
+        if bits::get_bit(p, i) == 1 {
+            bits::xor_into(&mut result, v);
+        }
+    }
+    result
+}
+
+// Coprime `f1 | f` and `g1 | g` with `f1 * g1 = lcm(f, g)`: the prime powers of `g / gcd`
+// are moved out of `f` until no common factor is left.
+fn coprime_split(f: &[u64], g: &[u64]) -> (Poly, Poly) {
+    let mut f1 = f.to_vec();
+    let mut g1 = gf2x::div(g, &gf2x::gcd(f, g));
+    loop {
+        let common = gf2x::gcd(&f1, &g1);
+        if gf2x::degree(&common) == Some(0) {
+            return (f1, g1);
+        }
+        f1 = gf2x::div(&f1, &common);
+        g1 = gf2x::mul(&g1, &common);
+    }
+}
+
+// Krylov basis of a vector whose minimal polynomial is the minimal polynomial of `a`.
+fn maximal_vector(a: &Matrix) -> (Subspace, Poly) {
+    let n = a.nrows();
+    if n == 0 {
+        return (Subspace::default(), gf2x::monomial(0));
+    }
+    let mut v = unit_vector(n, 0);
+    let (mut span, mut f) = krylov(a, &v);
+    // The minimal polynomial of `a` is the lcm of those of the unit vectors. `covered` is
+    // the sum of the Krylov subspaces met so far: it is invariant and the minimal
+    // polynomials of its vectors all divide `f`, so unit vectors inside it are skipped.
+    let mut covered = span.clone();
+    for i in 1..n {
+        if gf2x::degree(&f) == Some(n) || covered.pivots.len() == n {
+            break;
+        }
+        let e = unit_vector(n, i);
+        let mut reduced = e.clone();
+        covered.reduce(&mut reduced);
+        if bits::is_zero(&reduced) {
+            continue;
+        }
+        let (e_span, g) = krylov(a, &e);
+        covered.extend(e_span);
+        if gf2x::div_rem(&f, &g).1.is_empty() {
+            continue;
+        }
+        let (f1, g1) = coprime_split(&f, &g);
+        v = apply(a, &gf2x::div(&f, &f1), &v);
+        bits::xor_into(&mut v, &apply(a, &gf2x::div(&g, &g1), &e));
+        (span, f) = krylov(a, &v);
+    }
+    (span, f)
+}
+
+impl Matrix {
+    /// Characteristic polynomial of a square matrix.
+    pub fn charpoly(&self) -> Poly {
+        let mut a = self.clone();
+        let mut chi = gf2x::monomial(0);
+        while a.nrows() > 0 {
+            let (span, f) = krylov(&a, &unit_vector(a.nrows(), 0));
+            chi = gf2x::mul(&chi, &f);
+            a = quotient(&a, &span);
+        }
+        chi
+    }
+
+    /// Minimal polynomial of a square matrix.
+    pub fn minpoly(&self) -> Poly {
+        maximal_vector(self).1
+    }
+
+    /// Invariant factors `f_1 | f_2 | ... | f_k` of a square matrix, all of positive
+    /// degree: `f_k` is the minimal polynomial and their product the characteristic one.
+    pub fn invariant_factors(&self) -> Vec<Poly> {
+        let mut a = self.clone();
+        let mut factors = Vec::new();
+        while a.nrows() > 0 {
+            let (span, f) = maximal_vector(&a);
+            factors.push(f);
+            a = quotient(&a, &span);
+        }
+        factors.reverse();
+        factors
+    }
+
+    /// Frobenius normal form: the block diagonal matrix of the companion matrices of the
+    /// invariant factors, in the order of `invariant_factors`.
+    pub fn frobenius_form(&self) -> Matrix {
+        let mut form = Matrix::zeros(self.nrows(), self.nrows());
+        let mut offset = 0;
+        for f in self.invariant_factors() {
+            let d = gf2x::degree(&f).unwrap_or(0);
+            for i in 0..d {
+                if i + 1 < d {
+                    bits::set_bit(&mut form.rows[offset + i + 1], offset + i, 1);
+                }
+                bits::set_bit(&mut form.rows[offset + i], offset + d - 1, bits::get_bit(&f, i));
+            }
+            offset += d;
+        }
+        form
+    }
+}
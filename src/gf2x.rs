@@ -0,0 +1,108 @@
+// Polynomials over GF(2), packed like matrix rows: bit `i` is the coefficient of `x^i`.
+//
+// Polynomials are kept trimmed (no trailing zero words), so the zero polynomial is the
+// empty vector and equal polynomials compare equal.
+use crate::bits::{self, WORD_BITS};
+
+pub type Poly = Vec<u64>;
+
+/// The monomial `x^d`.
+pub fn monomial(d: usize) -> Poly {
+    let mut p = vec![0u64; d / WORD_BITS + 1];
+    bits::set_bit(&mut p, d, 1);
+    p
+}
+
+pub fn degree(p: &[u64]) -> Option<usize> {
+    let last = p.iter().rposition(|&w| w != 0)?;
+    Some(last * WORD_BITS + (WORD_BITS - 1 - p[last].leading_zeros() as usize))
+}
+
+fn trim(p: &mut Poly) {
+    while p.last() == Some(&0) {
+        p.pop();
+    }
+}
+
+/// `p += q`.
+pub fn add_assign(p: &mut Poly, q: &[u64]) {
+    if p.len() < q.len() {
+        p.resize(q.len(), 0);
+    }
+    bits::xor_into(p, q);
+    trim(p);
+}
+
+// `p += q * x^shift`, where `p` is long enough to hold the result.
+fn add_shifted(p: &mut [u64], q: &[u64], shift: usize) {
+    let (words, offset) = (shift / WORD_BITS, shift % WORD_BITS);
+    for (i, &w) in q.iter().enumerate().filter(|&(_, &w)| w != 0) {
+        p[i + words] ^= w << offset;
+        if offset != 0 && w >> (WORD_BITS - offset) != 0 {
+            p[i + words + 1] ^= w >> (WORD_BITS - offset);
+        }
+    }
+}
+
+pub fn mul(p: &[u64], q: &[u64]) -> Poly {
+    let (Some(dp), Some(dq)) = (degree(p), degree(q)) else {
+        return Vec::new();
+    };
+    let mut product = vec![0u64; bits::words_for(dp + dq + 1)];
+    let mut i = bits::first_one(p);
+    while let Some(shift) = i {
+        add_shifted(&mut product, q, shift);
+        i = bits::first_one_from(p, shift + 1);
+    }
+    trim(&mut product);
+    product
+}
+
+/// Quotient and remainder of `p` by the nonzero polynomial `q`.
+pub fn div_rem(p: &[u64], q: &[u64]) -> (Poly, Poly) {
+    let dq = degree(q).expect("division by the zero polynomial");
+    let mut remainder = p.to_vec();
+    trim(&mut remainder);
+    let mut quotient = vec![0u64; remainder.len()];
+    while let Some(dr) = degree(&remainder).filter(|&dr| dr >= dq) {
+        bits::set_bit(&mut quotient, dr - dq, 1);
+        add_shifted(&mut remainder, q, dr - dq);
+        trim(&mut remainder);
+    }
+    trim(&mut quotient);
+    (quotient, remainder)
+}
+
+/// `p / q`, for `q` dividing `p`.
+pub fn div(p: &[u64], q: &[u64]) -> Poly {
+    div_rem(p, q).0
+}
+
+pub fn gcd(p: &[u64], q: &[u64]) -> Poly {
+    let (mut a, mut b) = (p.to_vec(), q.to_vec());
+    trim(&mut a);
+    trim(&mut b);
+    while !b.is_empty() {
+        let r = div_rem(&a, &b).1;
+        a = std::mem::replace(&mut b, r);
+    }
+    a
+}
+
+/// `x^k mod m` for the exponent with little-endian words `k`, by square-and-multiply.
+pub fn pow_x_mod(k: &[u64], m: &[u64]) -> Poly {
+    let x = monomial(1);
+    let mut result = div_rem(&monomial(0), m).1;
+    for i in (0..k.len() * WORD_BITS).rev() {
+        result = div_rem(&mul(&result, &result), m).1;
+        if bits::get_bit(k, i) == 1 {
+            result = div_rem(&mul(&result, &x), m).1;
+        }
+    }
+    result
+}
+
+/// Coefficients from `x^0` up to the leading one.
+pub fn coefficients(p: &[u64]) -> Vec<u8> {
+    degree(p).map_or_else(Vec::new, |d| bits::unpack(p, d + 1))
+}
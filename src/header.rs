@@ -0,0 +1,9 @@
+// Constants of the matrix file header laid out in `mapped.rs`. They live on their own so
+// that `main.rs`, which cannot link against the library, writes the same header.
+
+pub const MAGIC: &[u8; 8] = b"GF2MATRX";
+pub const VERSION: u32 = 2;
+pub const HEADER_BYTES: usize = 64;
+
+/// Code of the graded monomial order (`MonomialOrder::Graded`).
+pub const GRADED: u32 = 1;
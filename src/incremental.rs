@@ -0,0 +1,194 @@
+use pyo3::prelude::*;
+use crate::bits;
+use crate::error::{self, Error};
+use crate::matrix::Matrix;
+
+/// Echelon form of a matrix `A` that grows one row or one column at a time.
+///
+/// Keeps `R = U * A` together with the transformation `U`. Every row of `R` is either
+/// zero or has a pivot (its leading one) that is the only one in its column, so sorting
+/// the non-zero rows of `R` by pivot gives the reduced row echelon form of `A`.
+/// Pushing a row costs `O(rank * ncols / 64)` and pushing a column `O(nrows^2 / 64)`,
+/// instead of re-reducing the whole matrix.
+#[pyclass]
+#[derive(Clone)]
+pub struct IncrementalEchelon {
+    reduced: Matrix,
+    transform: Matrix,
+    // Pivot column of each row of `reduced`, `None` for zero rows.
+    pivots: Vec<Option<usize>>,
+    // Pivot row of each column, `None` for free columns.
+    pivot_rows: Vec<Option<usize>>,
+    rank: usize,
+}
+
+#[pymethods]
+impl IncrementalEchelon {
+    #[new]
+    #[pyo3(signature = (ncols = 0))]
+    pub fn new(ncols: usize) -> Self {
+        IncrementalEchelon {
+            reduced: Matrix::zeros(0, ncols),
+            transform: Matrix::zeros(0, 0),
+            pivots: Vec::new(),
+            pivot_rows: vec![None; ncols],
+            rank: 0,
+        }
+    }
+
+    #[staticmethod]
+    pub fn from_matrix(m: PyRef<'_, Matrix>) -> Self {
+        let mut echelon = IncrementalEchelon::new(m.ncols());
+        for row in &m.rows {
+            echelon.push_packed_row(row.to_vec());
+        }
+        echelon
+    }
+
+    /// Appends a row to `A`; `row` must have `ncols()` entries.
+    #[pyo3(name = "push_row")]
+    fn py_push_row(&mut self, row: Vec<u8>) -> PyResult<()> {
+        if row.len() != self.ncols() {
+            return Err(Error::dimension(format!(
+                "row of length {} does not match {} columns",
+                row.len(),
+                self.ncols()
+            ))
+            .into());
+        }
+        error::check_binary(&row)?;
+        self.push_row(&row);
+        Ok(())
+    }
+
+    /// Appends a column to `A`; `column` must have `nrows()` entries.
+    #[pyo3(name = "push_column")]
+    fn py_push_column(&mut self, column: Vec<u8>) -> PyResult<()> {
+        if column.len() != self.nrows() {
+            return Err(Error::dimension(format!(
+                "column of length {} does not match {} rows",
+                column.len(),
+                self.nrows()
+            ))
+            .into());
+        }
+        error::check_binary(&column)?;
+        self.push_column(&column);
+        Ok(())
+    }
+
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    pub fn nrows(&self) -> usize {
+        self.reduced.nrows()
+    }
+
+    pub fn ncols(&self) -> usize {
+        self.reduced.ncols()
+    }
+
+    /// Kernel vector of `A` for its first free column, the same vector as
+    /// `A.kernel()[0]`, or `None` if `A` has full column rank.
+    pub fn kernel_vector(&self) -> Option<Vec<u8>> {
+        let free_col = self.pivot_rows.iter().position(|p| p.is_none())?;
+        let mut kernel_vector = vec![0u64; bits::words_for(self.ncols())];
+        bits::set_bit(&mut kernel_vector, free_col, 1);
+        for (row, pivot) in self.pivots.iter().enumerate() {
+            if let Some(p) = *pivot {
+                bits::set_bit(&mut kernel_vector, p, self.reduced.get(row, free_col));
+            }
+        }
+        Some(bits::unpack(&kernel_vector, self.ncols()))
+    }
+
+    /// Pivot columns in increasing order.
+    pub fn pivot_columns(&self) -> Vec<usize> {
+        (0..self.ncols()).filter(|&j| self.pivot_rows[j].is_some()).collect()
+    }
+
+    /// The matrix `R = U * A`, with rows in insertion order.
+    pub fn reduced(&self) -> Matrix {
+        self.reduced.clone()
+    }
+
+    /// The transformation `U` with `U * A = R`.
+    pub fn transform(&self) -> Matrix {
+        self.transform.clone()
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "IncrementalEchelon(nrows={}, ncols={}, rank={})",
+            self.nrows(),
+            self.ncols(),
+            self.rank
+        )
+    }
+}
+
+impl IncrementalEchelon {
+    pub fn push_row(&mut self, row: &[u8]) {
+        self.push_packed_row(bits::pack(row, self.ncols()));
+    }
+
+    fn push_packed_row(&mut self, mut row: Vec<u64>) {
+        let index = self.nrows();
+        let width = bits::words_for(index + 1);
+        self.transform.rows.set_stride(width);
+        self.transform.ncols = index + 1;
+        let mut u_row = vec![0u64; width];
+        bits::set_bit(&mut u_row, index, 1);
+
+        for (r, pivot) in self.pivots.iter().enumerate() {
+            if let Some(p) = *pivot {
+                if bits::get_bit(&row, p) == 1 {
+                    bits::xor_into(&mut row, &self.reduced.rows[r]);
+                    bits::xor_into(&mut u_row, &self.transform.rows[r]);
+                }
+            }
+        }
+
+        let pivot = bits::first_one(&row);
+        if let Some(p) = pivot {
+            for r in 0..index {
+                if bits::get_bit(&self.reduced.rows[r], p) == 1 {
+                    bits::xor_into(&mut self.reduced.rows[r], &row);
+                    bits::xor_into(&mut self.transform.rows[r], &u_row);
+                }
+            }
+            self.pivot_rows[p] = Some(index);
+            self.rank += 1;
+        }
+        self.pivots.push(pivot);
+        self.reduced.rows.push(&row);
+        self.transform.rows.push(&u_row);
+    }
+
+    pub fn push_column(&mut self, column: &[u8]) {
+        let col = self.ncols();
+        let packed = bits::pack(column, self.nrows());
+        self.reduced.ncols += 1;
+        self.reduced.rows.set_stride(bits::words_for(col + 1));
+        for (row, u_row) in self.reduced.rows.iter_mut().zip(self.transform.rows.iter()) {
+            bits::set_bit(row, col, bits::dot(u_row, &packed));
+        }
+        self.pivot_rows.push(None);
+
+        // A zero row that picks up a one becomes the pivot row of the new column.
+        let new_pivot = (0..self.nrows())
+            .find(|&r| self.pivots[r].is_none() && self.reduced.get(r, col) == 1);
+        if let Some(z) = new_pivot {
+            for r in (0..self.nrows()).filter(|&r| r != z) {
+                if self.reduced.get(r, col) == 1 {
+                    self.reduced.add_rows(r, z);
+                    self.transform.add_rows(r, z);
+                }
+            }
+            self.pivots[z] = Some(col);
+            self.pivot_rows[col] = Some(z);
+            self.rank += 1;
+        }
+    }
+}
@@ -0,0 +1,357 @@
+// Montgomery's block Lanczos over GF(2).
+//
+// The solver only touches the matrix `B` through products with blocks of 64 vectors,
+// stored one word per coordinate (bit `j` of word `k` is entry `k` of vector `j`), so
+// the memory cost is a handful of blocks besides `B` itself. It runs the symmetric
+// Lanczos recurrence on `A = B^T B` from `V_0 = A Y` for a random block `Y`, which gives
+// `X` with `A (X - Y) = 0` up to the last iterate `V_m`. Combinations of the 128 columns
+// of `[X - Y | V_m]` vanishing under `B` are then extracted with a small elimination.
+//
+// Reference: P. L. Montgomery, "A Block Lanczos Algorithm for Finding Dependencies
+// over GF(2)", EUROCRYPT 1995.
+use rand::RngCore;
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+use crate::bits;
+use crate::matrix::Matrix;
+use crate::random;
+use crate::sparse::SparseMatrix;
+
+const BLOCK: usize = 64;
+
+/// Number of fresh random starts tried when the recurrence breaks down.
+const MAX_ATTEMPTS: usize = 4;
+
+// A 64x64 matrix, one word per row.
+type Small = [u64; BLOCK];
+
+/// A linear map over GF(2) known only through products with blocks of 64 vectors.
+pub trait BlackBox: Sync {
+    fn nrows(&self) -> usize;
+    fn ncols(&self) -> usize;
+    /// `self * v` for a block `v` of `ncols()` words.
+    fn apply(&self, v: &[u64]) -> Vec<u64>;
+    /// `self^T * w` for a block `w` of `nrows()` words.
+    fn apply_transpose(&self, w: &[u64]) -> Vec<u64>;
+}
+
+impl BlackBox for SparseMatrix {
+    fn nrows(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    fn apply(&self, v: &[u64]) -> Vec<u64> {
+        self.rows
+            .par_iter()
+            .map(|row| row.iter().fold(0, |acc, &col| acc ^ v[col]))
+            .collect()
+    }
+
+    fn apply_transpose(&self, w: &[u64]) -> Vec<u64> {
+        let mut out = vec![0u64; self.ncols];
+        for (row, &word) in self.rows.iter().zip(w.iter()) {
+            if word != 0 {
+                for &col in row {
+                    out[col] ^= word;
+                }
+            }
+        }
+        out
+    }
+}
+
+/// `[B | b]`: `B` with the right-hand side `b` appended as a last column, so that kernel
+/// vectors ending in a one are solutions of `B x = b`.
+struct Augmented<'a, T: BlackBox> {
+    op: &'a T,
+    rhs: Vec<u8>,
+}
+
+impl<T: BlackBox> BlackBox for Augmented<'_, T> {
+    fn nrows(&self) -> usize {
+        self.op.nrows()
+    }
+
+    fn ncols(&self) -> usize {
+        self.op.ncols() + 1
+    }
+
+    fn apply(&self, v: &[u64]) -> Vec<u64> {
+        let last = v[self.op.ncols()];
+        let mut out = self.op.apply(&v[..self.op.ncols()]);
+        for (word, &b) in out.iter_mut().zip(self.rhs.iter()) {
+            if b & 1 == 1 {
+                *word ^= last;
+            }
+        }
+        out
+    }
+
+    fn apply_transpose(&self, w: &[u64]) -> Vec<u64> {
+        let mut out = self.op.apply_transpose(w);
+        let last = w
+            .iter()
+            .zip(self.rhs.iter())
+            .filter(|(_, &b)| b & 1 == 1)
+            .fold(0, |acc, (&word, _)| acc ^ word);
+        out.push(last);
+        out
+    }
+}
+
+/// Kernel vectors of `op`, as packed vectors of `op.ncols()` bits in reduced row echelon
+/// form. Up to about 64 independent vectors are found per run; an empty result means
+/// that no dependency was found, not that none exists.
+pub fn kernel<T: BlackBox>(op: &T, seed: Option<u64>) -> Vec<Vec<u64>> {
+    let mut rng = random::rng(seed);
+    for _ in 0..MAX_ATTEMPTS {
+        if let Some(vectors) = attempt(op, &mut rng) {
+            return vectors;
+        }
+    }
+    Vec::new()
+}
+
+/// A solution of `op * x = b`, or `None` if none was found.
+///
+/// Panics if `b.len() != op.nrows()`.
+pub fn solve<T: BlackBox>(op: &T, b: &[u8], seed: Option<u64>) -> Option<Vec<u64>> {
+    assert_eq!(b.len(), op.nrows(), "right-hand side does not match the number of rows");
+    let augmented = Augmented { op, rhs: b.to_vec() };
+    let n = op.ncols();
+    kernel(&augmented, seed)
+        .into_iter()
+        .find(|x| bits::get_bit(x, n) == 1)
+        .map(|x| bits::truncate(&x, n))
+}
+
+fn attempt<T: BlackBox>(op: &T, rng: &mut ChaCha8Rng) -> Option<Vec<Vec<u64>>> {
+    let n = op.ncols();
+    if n == 0 {
+        return Some(Vec::new());
+    }
+    let sym = |v: &[u64]| op.apply_transpose(&op.apply(v));
+
+    let y: Vec<u64> = (0..n).map(|_| rng.next_u64()).collect();
+    let v_start = sym(&y);
+    let (x, v_last) = lanczos(&sym, v_start, n)?;
+    let x: Vec<u64> = x.iter().zip(y.iter()).map(|(a, b)| a ^ b).collect();
+
+    // Column combinations of [x | v_last] whose image under B vanishes.
+    let bx = op.apply(&x);
+    let bv = op.apply(&v_last);
+    let combinations = column_kernel(bx.iter().zip(bv.iter()).map(|(&a, &b)| (a as u128) | ((b as u128) << BLOCK)));
+
+    let mut vectors = Matrix::zeros(combinations.len(), n);
+    for (k, (&xk, &vk)) in x.iter().zip(v_last.iter()).enumerate() {
+        let word = (xk as u128) | ((vk as u128) << BLOCK);
+        for (vector, combination) in vectors.rows.iter_mut().zip(combinations.iter()) {
+            bits::set_bit(vector, k, ((word & combination).count_ones() & 1) as u8);
+        }
+    }
+    Some(
+        vectors
+            .reduced()
+            .rows
+            .iter()
+            .filter(|row| !bits::is_zero(row))
+            .map(<[u64]>::to_vec)
+            .collect(),
+    )
+}
+
+// Runs the recurrence on the symmetric map `sym` from `v`, returning `X` with
+// `sym(X) = v` (up to the last iterate) and the last iterate `V_m`.
+fn lanczos<F: Fn(&[u64]) -> Vec<u64>>(sym: &F, v_start: Vec<u64>, n: usize) -> Option<(Vec<u64>, Vec<u64>)> {
+    let max_iterations = n / (BLOCK / 2) + 10;
+    let mut x = vec![0u64; n];
+    let mut v0 = v_start.clone();
+    let mut v1 = vec![0u64; n];
+    let mut v2 = vec![0u64; n];
+    let mut winv1: Small = [0; BLOCK];
+    let mut winv2: Small = [0; BLOCK];
+    let mut vt_a_v1: Small = [0; BLOCK];
+    let mut vt_a2_v1: Small = [0; BLOCK];
+    let mut mask1 = u64::MAX;
+
+    for _ in 0..max_iterations {
+        let av = sym(&v0);
+        let vt_a_v0 = inner(&v0, &av);
+        if vt_a_v0.iter().all(|&w| w == 0) {
+            return Some((x, v0));
+        }
+        let vt_a2_v0 = inner(&av, &av);
+        let (winv0, mask0) = nonsingular_subset(&vt_a_v0, mask1)?;
+        if mask0 == 0 {
+            return Some((x, v0));
+        }
+
+        // Solution update: X += V_i Winv_i V_i^T V_0.
+        let coeffs = mul_small(&winv0, &inner(&v0, &v_start));
+        bits::xor_into(&mut x, &mul_block(&v0, &coeffs));
+
+        // D = I - Winv_i (V_i^T A^2 V_i S_i S_i^T + V_i^T A V_i)
+        let mut d = mul_small(&winv0, &xor_small(&mask_columns(&vt_a2_v0, mask0), &vt_a_v0));
+        add_identity(&mut d);
+        // E = Winv_{i-1} V_i^T A V_i S_i S_i^T
+        let e = mul_small(&winv1, &mask_columns(&vt_a_v0, mask0));
+        // F = Winv_{i-2} (I - V_{i-1}^T A V_{i-1} Winv_{i-1})
+        //     (V_{i-1}^T A^2 V_{i-1} S_{i-1} S_{i-1}^T + V_{i-1}^T A V_{i-1}) S_i S_i^T
+        let mut g = mul_small(&vt_a_v1, &winv1);
+        add_identity(&mut g);
+        let h = mask_columns(&xor_small(&mask_columns(&vt_a2_v1, mask1), &vt_a_v1), mask0);
+        let f = mul_small(&mul_small(&winv2, &g), &h);
+
+        let mut v_next: Vec<u64> = av.iter().map(|&w| w & mask0).collect();
+        bits::xor_into(&mut v_next, &mul_block(&v0, &d));
+        bits::xor_into(&mut v_next, &mul_block(&v1, &e));
+        bits::xor_into(&mut v_next, &mul_block(&v2, &f));
+
+        v2 = std::mem::replace(&mut v1, std::mem::replace(&mut v0, v_next));
+        winv2 = winv1;
+        winv1 = winv0;
+        vt_a_v1 = vt_a_v0;
+        vt_a2_v1 = vt_a2_v0;
+        mask1 = mask0;
+    }
+    None
+}
+
+// Montgomery's choice of the columns S_i, with the inverse of `t` restricted to them.
+// Every column left out of the previous subset must be selected, otherwise the
+// recurrence has broken down and `None` is returned.
+fn nonsingular_subset(t: &Small, last_mask: u64) -> Option<(Small, u64)> {
+    let mut m: Vec<(u64, u64)> = (0..BLOCK).map(|i| (t[i], 1u64 << i)).collect();
+    let mut order: Vec<usize> = (0..BLOCK).filter(|&i| last_mask >> i & 1 == 0).collect();
+    order.extend((0..BLOCK).filter(|&i| last_mask >> i & 1 == 1));
+
+    let mut mask = 0u64;
+    for i in 0..BLOCK {
+        let col = order[i];
+        let bit = 1u64 << col;
+        if let Some(j) = (i..BLOCK).find(|&j| m[order[j]].0 & bit != 0) {
+            m.swap(order[i], order[j]);
+            let pivot = m[order[i]];
+            for &r in order.iter().filter(|&&r| r != col) {
+                if m[r].0 & bit != 0 {
+                    m[r].0 ^= pivot.0;
+                    m[r].1 ^= pivot.1;
+                }
+            }
+            mask |= bit;
+        } else {
+            let j = (i..BLOCK).find(|&j| m[order[j]].1 & bit != 0)?;
+            m.swap(order[i], order[j]);
+            let pivot = m[order[i]];
+            for &r in order.iter().filter(|&&r| r != col) {
+                if m[r].1 & bit != 0 {
+                    m[r].0 ^= pivot.0;
+                    m[r].1 ^= pivot.1;
+                }
+            }
+            m[col] = (0, 0);
+        }
+    }
+    if !last_mask & !mask != 0 {
+        return None;
+    }
+    let mut winv: Small = [0; BLOCK];
+    for (w, row) in winv.iter_mut().zip(m.iter()) {
+        *w = row.1;
+    }
+    Some((winv, mask))
+}
+
+// Kernel of the matrix whose rows are the given 128-bit words, as combinations of its
+// columns: each row is eliminated from the surviving columns in turn.
+fn column_kernel(rows: impl Iterator<Item = u128>) -> Vec<u128> {
+    let mut columns: Vec<u128> = (0..2 * BLOCK).map(|c| 1u128 << c).collect();
+    for row in rows.filter(|&row| row != 0) {
+        let Some(pivot) = columns.iter().position(|&c| (row & c).count_ones() & 1 == 1) else {
+            continue;
+        };
+        let pivot_column = columns.swap_remove(pivot);
+        for c in columns.iter_mut() {
+            if (row & *c).count_ones() & 1 == 1 {
+                *c ^= pivot_column;
+            }
+        }
+        if columns.is_empty() {
+            break;
+        }
+    }
+    columns
+}
+
+// v^T w for blocks of equal length.
+fn inner(v: &[u64], w: &[u64]) -> Small {
+    let mut tables = [[0u64; 256]; 8];
+    for (&vk, &wk) in v.iter().zip(w.iter()) {
+        for (b, table) in tables.iter_mut().enumerate() {
+            table[(vk >> (8 * b)) as usize & 0xff] ^= wk;
+        }
+    }
+    let mut out: Small = [0; BLOCK];
+    for (b, table) in tables.iter().enumerate() {
+        for (value, &word) in table.iter().enumerate().skip(1) {
+            for i in (0..8).filter(|&i| value >> i & 1 == 1) {
+                out[8 * b + i] ^= word;
+            }
+        }
+    }
+    out
+}
+
+// v * m for a block `v` and a 64x64 matrix `m`.
+fn mul_block(v: &[u64], m: &Small) -> Vec<u64> {
+    let mut tables = [[0u64; 256]; 8];
+    for (b, table) in tables.iter_mut().enumerate() {
+        for value in 1..256usize {
+            let low = value & (value - 1);
+            let i = (value ^ low).trailing_zeros() as usize;
+            table[value] = table[low] ^ m[8 * b + i];
+        }
+    }
+    v.par_iter()
+        .map(|&vk| {
+            tables
+                .iter()
+                .enumerate()
+                .fold(0, |acc, (b, table)| acc ^ table[(vk >> (8 * b)) as usize & 0xff])
+        })
+        .collect()
+}
+
+fn mul_small(a: &Small, b: &Small) -> Small {
+    let mut out: Small = [0; BLOCK];
+    for (o, &row) in out.iter_mut().zip(a.iter()) {
+        let mut bitset = row;
+        while bitset != 0 {
+            *o ^= b[bitset.trailing_zeros() as usize];
+            bitset &= bitset - 1;
+        }
+    }
+    out
+}
+
+fn xor_small(a: &Small, b: &Small) -> Small {
+    let mut out = *a;
+    for (o, &w) in out.iter_mut().zip(b.iter()) {
+        *o ^= w;
+    }
+    out
+}
+
+fn mask_columns(a: &Small, mask: u64) -> Small {
+    a.map(|w| w & mask)
+}
+
+fn add_identity(a: &mut Small) {
+    for (i, w) in a.iter_mut().enumerate() {
+        *w ^= 1 << i;
+    }
+}
@@ -1,5 +1,26 @@
+mod bits;
+mod echelon;
+mod error;
+mod formats;
+mod frobenius;
+mod gf2x;
+mod header;
+mod incremental;
+mod lanczos;
+mod m4ri;
+mod mapped;
 mod matrix;
+mod multiply;
+mod numpy;
+mod oplog;
+mod pluq;
+mod power;
+mod random;
 mod restricted_ai;
+mod rows;
+#[cfg(feature = "serde")]
+mod serialize;
+mod sparse;
 use pyo3::prelude::*;
 
 
@@ -7,8 +28,18 @@ use pyo3::prelude::*;
 #[pymodule]
 fn algebraic_immunity_utils(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<matrix::Matrix>()?;
+    m.add_class::<mapped::MappedMatrix>()?;
+    m.add_class::<numpy::PackedRows>()?;
+    m.add_class::<echelon::EchelonForm>()?;
+    m.add_class::<incremental::IncrementalEchelon>()?;
+    m.add_class::<oplog::OpLog>()?;
+    m.add_class::<oplog::RowOp>()?;
+    m.add_class::<pluq::Pluq>()?;
     m.add_class::<restricted_ai::RestrictedAI>()?;
-    m.add_function(wrap_pyfunction!(matrix::verify, m)?)?;
-    m.add_function(wrap_pyfunction!(matrix::verify_2, m)?)?;
+    m.add_class::<sparse::SparseMatrix>()?;
+    m.add("DimensionError", m.py().get_type::<error::DimensionError>())?;
+    m.add("NonBinaryValueError", m.py().get_type::<error::NonBinaryValueError>())?;
+    m.add_function(wrap_pyfunction!(matrix::py_verify, m)?)?;
+    m.add_function(wrap_pyfunction!(matrix::py_verify_2, m)?)?;
     Ok(())
 }
@@ -0,0 +1,191 @@
+// Method of the Four Russians (M4RI) elimination over GF(2).
+//
+// Pivots are collected in blocks of `k` columns. The `2^k` linear combinations of the
+// block's pivot rows are tabulated in Gray-code order (one row XOR per entry), and every
+// other row is then cleared on all `k` pivot columns with a single table lookup.
+use rayon::prelude::*;
+use crate::bits;
+use crate::matrix::Matrix;
+use crate::oplog::{OpLog, RowOp};
+
+/// Matrices with at least this many rows and columns are reduced with `echelonize`
+/// instead of the bit-by-bit elimination in `matrix.rs`.
+pub const M4RI_THRESHOLD: usize = 128;
+
+pub fn is_large(m: &Matrix) -> bool {
+    m.nrows() >= M4RI_THRESHOLD && m.ncols() >= M4RI_THRESHOLD
+}
+
+fn table_width(rows: usize) -> usize {
+    let log2 = (usize::BITS - rows.leading_zeros()) as usize;
+    (log2 * 3 / 4).clamp(1, 8)
+}
+
+/// Brings `m` to reduced row echelon form in place and returns its rank.
+///
+/// Pivots are only searched in the first `pivot_cols` columns; the remaining columns are
+/// carried along, e.g. an identity block that accumulates the transformation matrix.
+/// When `operations` is given, the row operations are recorded in it.
+pub fn echelonize(
+    m: &mut Matrix,
+    pivot_cols: usize,
+    mut operations: Option<&mut OpLog>,
+) -> usize {
+    let nrows = m.nrows();
+    let ncols = pivot_cols;
+    let k = table_width(nrows);
+    let logging = operations.is_some();
+    let mut log = |op: RowOp| {
+        if let Some(ops) = operations.as_deref_mut() {
+            ops.push(op);
+        }
+    };
+
+    let mut r = 0;
+    let mut c = 0;
+    while r < nrows && c < ncols {
+        let (pivots, next_c) = block_pivots(m, r, c, k, ncols, &mut log);
+        c = next_c;
+        if pivots.is_empty() {
+            break;
+        }
+
+        let first_word = pivots[0] / bits::WORD_BITS;
+        let table = gray_table(m, r, pivots.len(), first_word);
+        let width = m.rows[r].len() - first_word;
+        let block = r..r + pivots.len();
+
+        let lookup = |row: &[u64]| -> usize {
+            pivots
+                .iter()
+                .enumerate()
+                .map(|(j, &pc)| (bits::get_bit(row, pc) as usize) << j)
+                .sum()
+        };
+
+        if logging {
+            for i in (0..nrows).filter(|i| !block.contains(i)) {
+                let idx = lookup(&m.rows[i]);
+                if idx == 0 {
+                    continue;
+                }
+                bits::xor_into(&mut m.rows[i][first_word..], &table[idx * width..(idx + 1) * width]);
+                for j in 0..pivots.len() {
+                    if (idx >> j) & 1 == 1 {
+                        log(RowOp::AddRow { target: i, source: r + j });
+                    }
+                }
+            }
+        } else {
+            m.rows.par_iter_mut().enumerate().for_each(|(i, row)| {
+                if block.contains(&i) {
+                    return;
+                }
+                let idx = lookup(row);
+                if idx != 0 {
+                    bits::xor_into(&mut row[first_word..], &table[idx * width..(idx + 1) * width]);
+                }
+            });
+        }
+
+        r += pivots.len();
+    }
+
+    r
+}
+
+/// Clears the pivot columns of `pivot_rows` in every row of `target`.
+///
+/// `pivot_rows` must be in reduced row echelon form with `pivots[i]` the leading column
+/// of its row `i`; the rows are applied `table_width` at a time through Gray tables.
+pub fn clear_pivots(target: &mut Matrix, pivot_rows: &Matrix, pivots: &[usize]) {
+    let k = table_width(target.nrows());
+    for (g, group) in pivots.chunks(k).enumerate() {
+        let r = g * k;
+        let first_word = group[0] / bits::WORD_BITS;
+        let table = gray_table(pivot_rows, r, group.len(), first_word);
+        let width = pivot_rows.rows[r].len() - first_word;
+        target.rows.par_iter_mut().for_each(|row| {
+            let idx: usize = group
+                .iter()
+                .enumerate()
+                .map(|(j, &pc)| (bits::get_bit(row, pc) as usize) << j)
+                .sum();
+            if idx != 0 {
+                bits::xor_into(&mut row[first_word..], &table[idx * width..(idx + 1) * width]);
+            }
+        });
+    }
+}
+
+/// Finds up to `k` pivots in columns `c..ncols` among rows `r..`, moving them to rows
+/// `r..r + pivots.len()` and reducing them against each other on the pivot columns.
+/// Returns the pivot columns and the first column not yet examined.
+fn block_pivots<F: FnMut(RowOp)>(
+    m: &mut Matrix,
+    r: usize,
+    c: usize,
+    k: usize,
+    ncols: usize,
+    log: &mut F,
+) -> (Vec<usize>, usize) {
+    let nrows = m.nrows();
+    let mut pivots: Vec<usize> = Vec::with_capacity(k);
+    let mut col = c;
+
+    while pivots.len() < k && col < ncols && r + pivots.len() < nrows {
+        let pr = r + pivots.len();
+        let mut found = None;
+        for i in pr..nrows {
+            for (j, &pc) in pivots.iter().enumerate() {
+                if m.get(i, pc) == 1 {
+                    m.add_rows(i, r + j);
+                    log(RowOp::AddRow { target: i, source: r + j });
+                }
+            }
+            if m.get(i, col) == 1 {
+                found = Some(i);
+                break;
+            }
+        }
+
+        if let Some(i) = found {
+            if i != pr {
+                m.swap_rows(pr, i);
+                log(RowOp::Swap { row1: pr, row2: i });
+            }
+            for j in 0..pivots.len() {
+                if m.get(r + j, col) == 1 {
+                    m.add_rows(r + j, pr);
+                    log(RowOp::AddRow { target: r + j, source: pr });
+                }
+            }
+            pivots.push(col);
+        }
+        col += 1;
+    }
+
+    (pivots, col)
+}
+
+/// Tabulates all linear combinations of rows `r..r + kk`, restricted to words
+/// `first_word..`, indexed by the combination's bit mask.
+pub fn gray_table(m: &Matrix, r: usize, kk: usize, first_word: usize) -> Vec<u64> {
+    let width = m.rows[r].len() - first_word;
+    let mut table = vec![0u64; width << kk];
+    let mut prev = 0usize;
+    for i in 1..(1usize << kk) {
+        let gray = i ^ (i >> 1);
+        let changed = (gray ^ prev).trailing_zeros() as usize;
+        let (done, rest) = table.split_at_mut(gray.max(prev) * width);
+        let (dst, src) = if gray > prev {
+            (&mut rest[..width], &done[prev * width..(prev + 1) * width])
+        } else {
+            (&mut done[gray * width..(gray + 1) * width], &rest[..width])
+        };
+        dst.copy_from_slice(src);
+        bits::xor_into(dst, &m.rows[r + changed][first_word..]);
+        prev = gray;
+    }
+    table
+}
@@ -3,6 +3,9 @@ use std::io::{Write, BufWriter};
 use itertools::Itertools;
 use ndarray::Array2;
 
+mod header;
+use header::{GRADED, HEADER_BYTES, MAGIC, VERSION};
+
 // Generate binary strings of length `n` in graded lexicographic order
 fn generate_binary_strings(n: usize) -> Vec<String> {
     let mut binary_strings = Vec::new();
@@ -51,25 +54,40 @@ fn generate_matrix(n: usize) -> Array2<i32> {
 
 
 
-fn save_large_bit_matrix_bin(matrix: &Array2<i32>, filename: &str) {
-    let rows = matrix.nrows() as u32;
-    let cols = matrix.ncols() as u32;
+// Writes `matrix` in the bit-packed matrix file format documented in `mapped.rs`, with
+// the rows marked as the monomials in `n` variables in graded order. The files load with
+// `Matrix.load` and `MappedMatrix.open`.
+fn save_large_bit_matrix_bin(matrix: &Array2<i32>, n: usize, filename: &str) {
+    let rows = matrix.nrows() as u64;
+    let cols = matrix.ncols() as u64;
 
     let mut file = BufWriter::new(File::create(filename).expect("Failed to create file"));
 
-    file.write_all(&rows.to_le_bytes()).expect("Failed to write rows");
-    file.write_all(&cols.to_le_bytes()).expect("Failed to write cols");
+    let mut header = [0u8; HEADER_BYTES];
+    header[..8].copy_from_slice(MAGIC);
+    header[8..12].copy_from_slice(&VERSION.to_le_bytes());
+    header[12..16].copy_from_slice(&GRADED.to_le_bytes());
+    header[16..24].copy_from_slice(&rows.to_le_bytes());
+    header[24..32].copy_from_slice(&cols.to_le_bytes());
+    header[32..40].copy_from_slice(&(n as u64).to_le_bytes());
+    file.write_all(&header).expect("Failed to write header");
 
-    let data = matrix.as_slice().expect("Failed to convert matrix to slice");
-    for value in data {
-        file.write_all(&value.to_le_bytes()).expect("Failed to write matrix data");
+    for row in matrix.rows() {
+        let mut words = vec![0u64; row.len().div_ceil(64)];
+        for (j, &value) in row.iter().enumerate() {
+            words[j / 64] |= (value as u64 & 1) << (j % 64);
+        }
+        for word in words {
+            file.write_all(&word.to_le_bytes()).expect("Failed to write matrix data");
+        }
     }
+    file.flush().expect("Failed to flush file");
 }
 
 fn main() {
     let size = 16;
     let matrix = generate_matrix(size);
     let filename = format!("large_bit_matrix_{}.bin", size);
-    save_large_bit_matrix_bin(&matrix, &filename);
+    save_large_bit_matrix_bin(&matrix, size, &filename);
 
 }
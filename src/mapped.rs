@@ -0,0 +1,572 @@
+// Disk-backed GF(2) matrices, memory-mapped and bit-packed.
+//
+// File layout, all integers little-endian:
+//
+//   offset  size  field
+//        0     8  magic `b"GF2MATRX"`
+//        8     4  format version (`VERSION`)
+//       12     4  monomial order of the rows (`MonomialOrder`), 0 if none
+//       16     8  number of rows
+//       24     8  number of columns
+//       32     8  number of variables `n` of the monomial order, 0 if none
+//       40    24  reserved, zero
+//       64        rows, `words_for(ncols)` 64-bit words each, packed LSB-first as in `Matrix`
+//
+// Version 1 files have no monomial order; their bytes 12..16 and 32..40 are zero, so they
+// read as version 2 files without one.
+//
+// Elimination never holds more than two tiles of rows in memory. Tile `p` is brought to
+// reduced row echelon form, then every later tile is streamed in and cleared on the
+// pivots of tile `p`. Pivots are leading ones, so the pivot columns are the column rank
+// profile and the kernel basis matches `Matrix.kernel`.
+use std::fs::{self, File};
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use memmap2::MmapMut;
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::prelude::*;
+use crate::bits;
+use crate::error::{self, Error, NonBinaryValueError};
+use crate::header::{GRADED, HEADER_BYTES, MAGIC, VERSION};
+use crate::m4ri;
+use crate::matrix::Matrix;
+use crate::rows::Rows;
+
+/// Memory budget of one tile when no tile size is given.
+const TILE_BYTES: usize = 64 << 20;
+
+/// Order of the monomials indexing the rows of a matrix file, with the columns indexed by
+/// the `2^n` points.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MonomialOrder {
+    /// Monomials in `x_0, ..., x_{n-1}` by degree, then lexicographically by their sorted
+    /// variable indices: `1, x_0, ..., x_{n-1}, x_0 x_1, x_0 x_2, ...`. Column `c` is the
+    /// point with `x_i` equal to bit `i` of `c`. This is the order written by `main.rs`.
+    Graded = GRADED as isize,
+}
+
+impl MonomialOrder {
+    pub fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "graded" => Ok(MonomialOrder::Graded),
+            _ => Err(PyValueError::new_err(format!("monomial_order must be 'graded', got {:?}", name))),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MonomialOrder::Graded => "graded",
+        }
+    }
+
+    fn from_code(code: u32) -> PyResult<Option<Self>> {
+        match code {
+            0 => Ok(None),
+            GRADED => Ok(Some(MonomialOrder::Graded)),
+            _ => Err(PyValueError::new_err(format!("unknown monomial order {} in matrix file", code))),
+        }
+    }
+}
+
+/// The fields of a matrix file header.
+pub struct Header {
+    pub nrows: usize,
+    pub ncols: usize,
+    /// The monomial order of the rows and its number of variables.
+    pub monomials: Option<(MonomialOrder, usize)>,
+}
+
+impl Header {
+    /// Checks that `monomial_order` and `n` are given together and fit the shape.
+    pub fn new(nrows: usize, ncols: usize, monomial_order: Option<&str>, n: Option<usize>) -> PyResult<Self> {
+        let monomials = match (monomial_order, n) {
+            (None, None) => None,
+            (Some(order), Some(n)) => {
+                let order = MonomialOrder::parse(order)?;
+                let points = u32::try_from(n).ok().and_then(|n| 1usize.checked_shl(n));
+                if points != Some(ncols) || nrows > ncols {
+                    return Err(Error::dimension(format!(
+                        "a {}x{} matrix cannot be indexed by monomials and points in {} variables",
+                        nrows, ncols, n
+                    ))
+                    .into());
+                }
+                Some((order, n))
+            }
+            _ => return Err(PyValueError::new_err("monomial_order and n must be given together")),
+        };
+        let header = Header { nrows, ncols, monomials };
+        header.file_len()?;
+        Ok(header)
+    }
+
+    /// Length of the file, checked since the shape may come from an untrusted header.
+    fn file_len(&self) -> PyResult<usize> {
+        (bits::words_for(self.ncols) * 8)
+            .checked_mul(self.nrows)
+            .and_then(|len| len.checked_add(HEADER_BYTES))
+            .ok_or_else(|| {
+                Error::dimension(format!("a {}x{} matrix is too large for a matrix file", self.nrows, self.ncols)).into()
+            })
+    }
+
+    fn encode(&self) -> [u8; HEADER_BYTES] {
+        let (order, n) = self.monomials.map_or((0, 0), |(order, n)| (order as u32, n));
+        let mut header = [0u8; HEADER_BYTES];
+        header[..8].copy_from_slice(MAGIC);
+        header[8..12].copy_from_slice(&VERSION.to_le_bytes());
+        header[12..16].copy_from_slice(&order.to_le_bytes());
+        header[16..24].copy_from_slice(&(self.nrows as u64).to_le_bytes());
+        header[24..32].copy_from_slice(&(self.ncols as u64).to_le_bytes());
+        header[32..40].copy_from_slice(&(n as u64).to_le_bytes());
+        header
+    }
+
+    /// Parses the header of the file at `path` with contents `data`, checking its length.
+    fn decode(data: &[u8], path: &Path) -> PyResult<Self> {
+        if data.len() < HEADER_BYTES || &data[..8] != MAGIC {
+            return Err(PyValueError::new_err(format!(
+                "{} is not a GF(2) matrix file; files of 32-bit entries written by older versions of \
+                 main.rs can be converted with MappedMatrix.convert_legacy",
+                path.display()
+            )));
+        }
+        let word = |start: usize| u64::from_le_bytes(data[start..start + 8].try_into().unwrap()) as usize;
+        let version = u32::from_le_bytes(data[8..12].try_into().unwrap());
+        if version == 0 || version > VERSION {
+            return Err(PyValueError::new_err(format!("unsupported matrix file version {}", version)));
+        }
+        let order = MonomialOrder::from_code(u32::from_le_bytes(data[12..16].try_into().unwrap()))?;
+        let header = Header {
+            nrows: word(16),
+            ncols: word(24),
+            monomials: order.map(|order| (order, word(32))),
+        };
+        let len = header.file_len()?;
+        if data.len() != len {
+            return Err(PyValueError::new_err(format!(
+                "{} has {} bytes, expected {} for a {}x{} matrix",
+                path.display(),
+                data.len(),
+                len,
+                header.nrows,
+                header.ncols
+            )));
+        }
+        Ok(header)
+    }
+}
+
+/// Reads a whole matrix file into memory, without mapping it.
+pub fn read_matrix(path: &Path) -> PyResult<Matrix> {
+    let data = fs::read(path)?;
+    let header = Header::decode(&data, path)?;
+    // The rows are laid out in the file as they are in memory.
+    let words = data[HEADER_BYTES..]
+        .chunks_exact(8)
+        .map(|w| u64::from_le_bytes(w.try_into().unwrap()))
+        .collect();
+    let rows = Rows::from_words(words, header.nrows, bits::words_for(header.ncols));
+    Ok(Matrix { rows, ncols: header.ncols })
+}
+
+/// Writes `m` to a matrix file at `path`, without a monomial order.
+pub fn write_matrix(m: &Matrix, path: &Path) -> PyResult<()> {
+    let header = Header { nrows: m.nrows(), ncols: m.ncols(), monomials: None };
+    let mut data = Vec::with_capacity(header.file_len()?);
+    data.extend_from_slice(&header.encode());
+    for row in &m.rows {
+        data.extend(row.iter().flat_map(|w| w.to_le_bytes()));
+    }
+    fs::write(path, data)?;
+    Ok(())
+}
+
+#[pyclass]
+pub struct MappedMatrix {
+    path: PathBuf,
+    mmap: MmapMut,
+    nrows: usize,
+    ncols: usize,
+    monomials: Option<(MonomialOrder, usize)>,
+}
+
+#[pymethods]
+impl MappedMatrix {
+    /// Creates a zero `nrows x ncols` matrix backed by the file at `path`, replacing it
+    /// if it exists. The header can record that the rows are monomials in `n` variables
+    /// in `monomial_order` ("graded") and the columns the `2^n` points.
+    #[staticmethod]
+    #[pyo3(signature = (path, nrows, ncols, monomial_order = None, n = None))]
+    pub fn create(
+        path: PathBuf,
+        nrows: usize,
+        ncols: usize,
+        monomial_order: Option<&str>,
+        n: Option<usize>,
+    ) -> PyResult<Self> {
+        let header = Header::new(nrows, ncols, monomial_order, n)?;
+        let file = File::options()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)?;
+        file.set_len(header.file_len()? as u64)?;
+        // SAFETY: the file was just created by us; callers must not resize it while mapped.
+        let mut mmap = unsafe { MmapMut::map_mut(&file)? };
+        mmap[..HEADER_BYTES].copy_from_slice(&header.encode());
+        Ok(MappedMatrix { path, mmap, nrows, ncols, monomials: header.monomials })
+    }
+
+    /// Maps an existing matrix file.
+    #[staticmethod]
+    pub fn open(path: PathBuf) -> PyResult<Self> {
+        let file = File::options().read(true).write(true).open(&path)?;
+        // SAFETY: as in `create`, the file must not be resized while mapped.
+        let mmap = unsafe { MmapMut::map_mut(&file)? };
+        let Header { nrows, ncols, monomials } = Header::decode(&mmap, &path)?;
+        Ok(MappedMatrix { path, mmap, nrows, ncols, monomials })
+    }
+
+    /// Converts a file written by older versions of `main.rs`, made of a `u32` row count,
+    /// a `u32` column count and every entry as an `i32`, row by row and all little-endian,
+    /// into a matrix file at `dst`. Rows are streamed, so the 32-bit entries are never all
+    /// in memory. With a monomial order, `n` is read off the `2^n` columns.
+    #[staticmethod]
+    #[pyo3(signature = (src, dst, monomial_order = Some("graded")))]
+    pub fn convert_legacy(src: PathBuf, dst: PathBuf, monomial_order: Option<&str>) -> PyResult<Self> {
+        let file = File::open(&src)?;
+        let len = file.metadata()?.len();
+        if len < 8 {
+            return Err(PyValueError::new_err(format!("{} is too short for a matrix file", src.display())));
+        }
+        let mut reader = BufReader::new(file);
+        let mut dims = [0u8; 8];
+        reader.read_exact(&mut dims)?;
+        let nrows = u32::from_le_bytes(dims[..4].try_into().unwrap()) as usize;
+        let ncols = u32::from_le_bytes(dims[4..].try_into().unwrap()) as usize;
+        if len != 8 + 4 * nrows as u64 * ncols as u64 {
+            return Err(PyValueError::new_err(format!(
+                "{} has {} bytes, expected {} for a {}x{} matrix of 32-bit entries",
+                src.display(),
+                len,
+                8 + 4 * nrows as u64 * ncols as u64,
+                nrows,
+                ncols
+            )));
+        }
+        let n = monomial_order.map(|_| ncols.trailing_zeros() as usize);
+        let mut mapped = MappedMatrix::create(dst, nrows, ncols, monomial_order, n)?;
+        let mut entries = vec![0u8; 4 * ncols];
+        for i in 0..nrows {
+            reader.read_exact(&mut entries)?;
+            let mut row = vec![0u8; ncols];
+            for (j, entry) in entries.chunks_exact(4).enumerate() {
+                match i32::from_le_bytes(entry.try_into().unwrap()) {
+                    value @ (0 | 1) => row[j] = value as u8,
+                    value => {
+                        return Err(NonBinaryValueError::new_err(format!(
+                            "entry ({}, {}) of {} is {}, expected 0 or 1",
+                            i,
+                            j,
+                            src.display(),
+                            value
+                        )))
+                    }
+                }
+            }
+            mapped.write_words(i, 0, &bits::pack(&row, ncols));
+        }
+        mapped.flush()?;
+        Ok(mapped)
+    }
+
+    /// Writes `m` to a new matrix file at `path`.
+    #[staticmethod]
+    pub fn from_matrix(path: PathBuf, m: PyRef<'_, Matrix>) -> PyResult<Self> {
+        let mut mapped = MappedMatrix::create(path, m.nrows(), m.ncols(), None, None)?;
+        mapped.write_rows(0, &m);
+        Ok(mapped)
+    }
+
+    /// Loads the whole matrix into memory.
+    pub fn to_matrix(&self) -> Matrix {
+        self.read_rows(0, self.nrows)
+    }
+
+    /// Rows `start..end` as an in-memory `Matrix`.
+    pub fn get_sub_matrix(&self, start: usize, end: usize) -> PyResult<Matrix> {
+        if start > end || end > self.nrows {
+            return Err(Error::Range { start, end, len: self.nrows }.into());
+        }
+        Ok(self.read_rows(start, end - start))
+    }
+
+    /// Overwrites rows `start..start + m.nrows()` with the rows of `m`.
+    #[pyo3(name = "set_sub_matrix")]
+    fn py_set_sub_matrix(&mut self, start: usize, m: PyRef<'_, Matrix>) -> PyResult<()> {
+        if m.ncols() != self.ncols || start + m.nrows() > self.nrows {
+            return Err(Error::dimension(format!(
+                "cannot write a {}x{} block at row {} of a {}x{} matrix",
+                m.nrows(),
+                m.ncols(),
+                start,
+                self.nrows,
+                self.ncols
+            ))
+            .into());
+        }
+        self.write_rows(start, &m);
+        Ok(())
+    }
+
+    pub fn nrows(&self) -> usize {
+        self.nrows
+    }
+
+    pub fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    #[getter]
+    pub fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    /// Order of the monomials indexing the rows, if recorded in the header.
+    #[getter]
+    pub fn monomial_order(&self) -> Option<&'static str> {
+        self.monomials.map(|(order, _)| order.name())
+    }
+
+    /// Number of variables of the monomials indexing the rows, if recorded in the header.
+    #[getter]
+    pub fn n(&self) -> Option<usize> {
+        self.monomials.map(|(_, n)| n)
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> PyResult<u8> {
+        self.check_index(row, col)?;
+        let word = self.read_word(row, col / bits::WORD_BITS);
+        Ok(((word >> (col % bits::WORD_BITS)) & 1) as u8)
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, value: u8) -> PyResult<()> {
+        self.check_index(row, col)?;
+        error::check_binary(&[value])?;
+        let mut word = [self.read_word(row, col / bits::WORD_BITS)];
+        bits::set_bit(&mut word, col % bits::WORD_BITS, value);
+        self.write_words(row, col / bits::WORD_BITS, &word);
+        Ok(())
+    }
+
+    /// Flushes pending writes to disk.
+    pub fn flush(&self) -> PyResult<()> {
+        Ok(self.mmap.flush()?)
+    }
+
+    /// Brings the matrix to a block echelon form in place and returns its rank.
+    ///
+    /// Each tile of `tile_rows` rows ends up in reduced row echelon form with its pivot
+    /// rows first, and no row has a one on the pivot column of an earlier tile. The
+    /// default tile size keeps each tile around 64 MiB.
+    #[pyo3(signature = (tile_rows = None))]
+    pub fn echelonize(&mut self, py: Python<'_>, tile_rows: Option<usize>) -> usize {
+        let tile_rows = self.tile_rows(tile_rows);
+        py.allow_threads(|| self.echelonize_tiles(tile_rows))
+            .iter()
+            .map(|(_, pivots)| pivots.len())
+            .sum()
+    }
+
+    /// Rank, computed on a scratch copy of the file so the matrix is left untouched.
+    #[pyo3(signature = (tile_rows = None))]
+    pub fn rank(&self, py: Python<'_>, tile_rows: Option<usize>) -> PyResult<usize> {
+        let mut scratch = self.scratch_copy()?;
+        let rank = scratch.echelonize(py, tile_rows);
+        scratch.remove()?;
+        Ok(rank)
+    }
+
+    /// Writes a basis of the right kernel to a new matrix file at `path`, one vector
+    /// per row, and returns it. The basis is the one returned by `Matrix.kernel`.
+    #[pyo3(signature = (path, tile_rows = None))]
+    pub fn kernel(&self, py: Python<'_>, path: PathBuf, tile_rows: Option<usize>) -> PyResult<MappedMatrix> {
+        let tile_rows = self.tile_rows(tile_rows);
+        let mut scratch = self.scratch_copy()?;
+        let tiles = py.allow_threads(|| scratch.echelonize_tiles(tile_rows));
+        let kernel = py.allow_threads(|| scratch.kernel_from_tiles(&tiles, path, tile_rows));
+        scratch.remove()?;
+        kernel
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "MappedMatrix(path={:?}, nrows={}, ncols={})",
+            self.path.display().to_string(),
+            self.nrows,
+            self.ncols
+        )
+    }
+}
+
+impl MappedMatrix {
+    fn row_bytes(&self) -> usize {
+        bits::words_for(self.ncols) * 8
+    }
+
+    fn word_offset(&self, row: usize, word: usize) -> usize {
+        HEADER_BYTES + row * self.row_bytes() + word * 8
+    }
+
+    fn read_word(&self, row: usize, word: usize) -> u64 {
+        let offset = self.word_offset(row, word);
+        u64::from_le_bytes(self.mmap[offset..offset + 8].try_into().unwrap())
+    }
+
+    fn write_words(&mut self, row: usize, word: usize, words: &[u64]) {
+        let offset = self.word_offset(row, word);
+        for (chunk, w) in self.mmap[offset..offset + words.len() * 8].chunks_exact_mut(8).zip(words) {
+            chunk.copy_from_slice(&w.to_le_bytes());
+        }
+    }
+
+    pub(crate) fn read_rows(&self, start: usize, count: usize) -> Matrix {
+        let row_bytes = self.row_bytes();
+        let offset = self.word_offset(start, 0);
+        let words = self.mmap[offset..offset + count * row_bytes]
+            .chunks_exact(8)
+            .map(|w| u64::from_le_bytes(w.try_into().unwrap()))
+            .collect();
+        Matrix { rows: Rows::from_words(words, count, bits::words_for(self.ncols)), ncols: self.ncols }
+    }
+
+    pub(crate) fn write_rows(&mut self, start: usize, m: &Matrix) {
+        for (i, row) in m.rows.iter().enumerate() {
+            self.write_words(start + i, 0, row);
+        }
+    }
+
+    fn check_index(&self, row: usize, col: usize) -> PyResult<()> {
+        if row >= self.nrows || col >= self.ncols {
+            return Err(PyIndexError::new_err(format!(
+                "index ({}, {}) out of range for a {}x{} matrix",
+                row, col, self.nrows, self.ncols
+            )));
+        }
+        Ok(())
+    }
+
+    // Tiles are a whole number of words tall, so that transposed tiles stay word-aligned.
+    fn tile_rows(&self, tile_rows: Option<usize>) -> usize {
+        let rows = tile_rows.unwrap_or(TILE_BYTES / self.row_bytes().max(1));
+        rows.max(1).next_multiple_of(bits::WORD_BITS)
+    }
+
+    fn scratch_copy(&self) -> PyResult<MappedMatrix> {
+        self.flush()?;
+        let scratch = sibling(&self.path, "scratch");
+        fs::copy(&self.path, &scratch)?;
+        MappedMatrix::open(scratch)
+    }
+
+    fn remove(self) -> PyResult<()> {
+        let MappedMatrix { path, mmap, .. } = self;
+        drop(mmap);
+        Ok(fs::remove_file(path)?)
+    }
+
+    // Returns the first row and the pivot columns of every tile; the pivot rows of a tile
+    // are its first rows.
+    fn echelonize_tiles(&mut self, tile_rows: usize) -> Vec<(usize, Vec<usize>)> {
+        let starts: Vec<usize> = (0..self.nrows).step_by(tile_rows).collect();
+        let nrows = self.nrows;
+        let len = |start: usize| tile_rows.min(nrows - start);
+        let mut tiles = Vec::with_capacity(starts.len());
+
+        for (p, &start) in starts.iter().enumerate() {
+            let mut tile = self.read_rows(start, len(start));
+            let rank = m4ri::echelonize(&mut tile, self.ncols, None);
+            let pivots: Vec<usize> = tile
+                .rows
+                .iter()
+                .take(rank)
+                .map(|row| bits::first_one(row).expect("pivot rows are non-zero"))
+                .collect();
+            self.write_rows(start, &tile);
+
+            if !pivots.is_empty() {
+                for &other in &starts[p + 1..] {
+                    let mut target = self.read_rows(other, len(other));
+                    m4ri::clear_pivots(&mut target, &tile, &pivots);
+                    self.write_rows(other, &target);
+                }
+            }
+            tiles.push((start, pivots));
+        }
+        tiles
+    }
+
+    // Back substitution over the tiles, last tile first. The kernel vectors are built as
+    // the columns of an `ncols x nfree` scratch matrix `X`, whose rows for the pivots of a
+    // tile are the tile's pivot rows times `X`, then `X` is transposed into the output.
+    fn kernel_from_tiles(&self, tiles: &[(usize, Vec<usize>)], path: PathBuf, tile_rows: usize) -> PyResult<MappedMatrix> {
+        let mut is_pivot = vec![false; self.ncols];
+        for &c in tiles.iter().flat_map(|(_, pivots)| pivots) {
+            is_pivot[c] = true;
+        }
+        let free: Vec<usize> = (0..self.ncols).filter(|&j| !is_pivot[j]).collect();
+        if free.is_empty() {
+            return MappedMatrix::create(path, 0, self.ncols, None, None);
+        }
+
+        let mut x = MappedMatrix::create(sibling(&path, "x"), self.ncols, free.len(), None, None)?;
+        for (k, &f) in free.iter().enumerate() {
+            x.set(f, k, 1)?;
+        }
+
+        for (start, pivots) in tiles.iter().rev().filter(|(_, pivots)| !pivots.is_empty()) {
+            let tile = self.read_rows(*start, pivots.len());
+            let mut values = Matrix::zeros(pivots.len(), free.len());
+            for chunk in (0..self.ncols).step_by(tile_rows) {
+                let end = (chunk + tile_rows).min(self.ncols);
+                let x_chunk = x.read_rows(chunk, end - chunk);
+                if x_chunk.rows.iter().all(bits::is_zero) {
+                    continue;
+                }
+                let product = tile.column_block(chunk, end).mul(&x_chunk);
+                for (dst, src) in values.rows.iter_mut().zip(product.rows.iter()) {
+                    bits::xor_into(dst, src);
+                }
+            }
+            for (row, &c) in values.rows.iter().zip(pivots.iter()) {
+                x.write_words(c, 0, row);
+            }
+        }
+
+        let mut kernel = MappedMatrix::create(path, free.len(), self.ncols, None, None)?;
+        for chunk in (0..self.ncols).step_by(tile_rows) {
+            let end = (chunk + tile_rows).min(self.ncols);
+            let block = x.read_rows(chunk, end - chunk).transposed();
+            for (k, row) in block.rows.iter().enumerate() {
+                kernel.write_words(k, chunk / bits::WORD_BITS, row);
+            }
+        }
+        x.remove()?;
+        Ok(kernel)
+    }
+}
+
+// Scratch files made by this process so far, to keep their names apart.
+static SCRATCH_FILES: AtomicUsize = AtomicUsize::new(0);
+
+// A fresh scratch file next to `path`: `path` with `.suffix.<pid>.<n>` appended, so that
+// concurrent calls, from this process or another, never share one.
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    let n = SCRATCH_FILES.fetch_add(1, Ordering::Relaxed);
+    name.push(format!(".{}.{}.{}", suffix, std::process::id(), n));
+    PathBuf::from(name)
+}
@@ -1,173 +1,1068 @@
+use std::path::PathBuf;
+use pyo3::exceptions::{PyTypeError, PyValueError};
 use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyInt, PyIterator, PyList, PySlice, PyTuple};
+use crate::bits;
+use crate::echelon::EchelonForm;
+use crate::error::{self, Error, NonBinaryValueError};
+use crate::formats::{self, Format};
+use crate::gf2x;
+use crate::m4ri;
+use crate::numpy::{self, BitOrder, PackedRows};
+use crate::oplog::OpLog;
+use crate::pluq::Pluq;
+use crate::random;
+use crate::rows::Rows;
+#[cfg(feature = "serde")]
+use crate::serialize;
 
-#[pyclass]
-#[derive(Clone)]
+/// A particular solution together with a basis of the homogeneous solutions.
+pub(crate) type AffineSolution = (Vec<u8>, Vec<Vec<u8>>);
+/// Per-column particular solutions (`None` when inconsistent) and the shared homogeneous basis.
+type BatchSolution = (Vec<Option<Vec<u8>>>, Vec<Vec<u8>>);
+/// Pickled state: `(nrows, ncols, to_bytes())`.
+type State<'py> = (usize, usize, Bound<'py, PyBytes>);
+
+/// Row operations recorded by the elimination routines. When a transform is tracked,
+/// every operation is mirrored on it, so that `transform * A` is the reduced matrix.
+pub(crate) struct RowOps {
+    pub(crate) operations: OpLog,
+    pub(crate) transform: Option<Matrix>,
+}
+
+impl RowOps {
+    pub(crate) fn new(nrows: usize, with_transform: bool) -> Self {
+        RowOps {
+            operations: OpLog::default(),
+            transform: with_transform.then(|| Matrix::identity(nrows)),
+        }
+    }
+
+    fn add(&mut self, target: usize, source: usize) {
+        self.operations.add_row(target, source);
+        if let Some(transform) = self.transform.as_mut() {
+            transform.add_rows(target, source);
+        }
+    }
+
+    fn swap(&mut self, row1: usize, row2: usize) {
+        self.operations.swap(row1, row2);
+        if let Some(transform) = self.transform.as_mut() {
+            transform.swap_rows(row1, row2);
+        }
+    }
+}
+
+/// Span of packed rows of length `ncols`, grown one row at a time. Only the rank is
+/// kept track of, so inserting a row costs `O(rank * ncols / 64)` and nothing else.
+pub(crate) struct RowBasis {
+    // `basis[p]` is the kept row whose leading one is in column `p`.
+    basis: Vec<Option<Vec<u64>>>,
+    rank: usize,
+}
+
+impl RowBasis {
+    pub(crate) fn new(ncols: usize) -> Self {
+        RowBasis { basis: vec![None; ncols], rank: 0 }
+    }
+
+    /// Adds `row` to the span; returns whether it raised the rank.
+    pub(crate) fn insert(&mut self, mut row: Vec<u64>) -> bool {
+        while let Some(p) = Matrix::get_pivot(&row) {
+            match &self.basis[p] {
+                Some(pivot_row) => bits::xor_into(&mut row, pivot_row),
+                None => {
+                    self.basis[p] = Some(row);
+                    self.rank += 1;
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    pub(crate) fn rank(&self) -> usize {
+        self.rank
+    }
+}
+
+#[pyclass(eq, module = "algebraic_immunity_utils")]
+#[derive(Clone, PartialEq, Eq)]
 pub struct Matrix {
-    pub elements: Vec<Vec<u8>>,
+    // Each row is packed into `bits::words_for(ncols)` words; unused high bits stay zero.
+    pub(crate) rows: Rows,
+    pub(crate) ncols: usize,
 }
 
 #[pymethods]
 impl Matrix {
+    /// Builds a matrix from a list of rows of equal length, with entries 0 or 1.
     #[new]
-    pub fn new(elements: Vec<Vec<u8>>) -> Self {
-        Matrix { elements }
+    fn py_new(elements: Vec<Vec<i64>>) -> PyResult<Self> {
+        let ncols = elements.first().map_or(0, |row| row.len());
+        for (i, row) in elements.iter().enumerate() {
+            if row.len() != ncols {
+                return Err(Error::dimension(format!(
+                    "row {} has length {}, expected {}",
+                    i,
+                    row.len(),
+                    ncols
+                ))
+                .into());
+            }
+            error::check_binary(row)?;
+        }
+        let rows = elements
+            .iter()
+            .map(|row| bits::pack(&row.iter().map(|&v| v as u8).collect::<Vec<_>>(), ncols));
+        Ok(Matrix::from_rows(rows, ncols))
     }
 
-    pub fn get_sub_matrix(&self, start: usize, end: usize) -> Self {
-        Self {
-            elements: self.elements[start..end].to_vec(),
+    #[staticmethod]
+    pub fn zeros(nrows: usize, ncols: usize) -> Self {
+        Matrix {
+            rows: Rows::zeros(nrows, bits::words_for(ncols)),
+            ncols,
+        }
+    }
+
+    #[staticmethod]
+    pub fn identity(n: usize) -> Self {
+        let mut m = Matrix::zeros(n, n);
+        for (i, row) in m.rows.iter_mut().enumerate() {
+            bits::set_bit(row, i, 1);
+        }
+        m
+    }
+
+    /// Builds a matrix from one Python int per row, bit `j` of `ints[i]` being entry
+    /// `(i, j)`.
+    #[staticmethod]
+    pub fn from_int_rows(ints: Vec<Bound<'_, PyInt>>, ncols: usize) -> PyResult<Self> {
+        let nbytes = ncols.div_ceil(8);
+        let rows = ints
+            .iter()
+            .map(|int| {
+                if int.lt(0)? {
+                    return Err(NonBinaryValueError::new_err(format!("{} is negative, rows must be non-negative", int)));
+                }
+                if int.call_method0("bit_length")?.extract::<usize>()? > ncols {
+                    return Err(Error::dimension(format!("{} does not fit in {} columns", int, ncols)).into());
+                }
+                let bytes: Vec<u8> = int.call_method1("to_bytes", (nbytes, "little"))?.extract()?;
+                Ok(bytes_to_row(&bytes, ncols))
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(Matrix::from_rows(rows, ncols))
+    }
+
+    /// Builds a matrix from packed rows: each row takes `ceil(ncols / 8)` bytes and bit
+    /// `j` of a row is bit `j % 8` of its byte `j / 8`. Bits past `ncols` are ignored.
+    #[staticmethod]
+    pub fn from_bytes(data: &[u8], nrows: usize, ncols: usize) -> PyResult<Self> {
+        let row_bytes = ncols.div_ceil(8);
+        if data.len() != nrows * row_bytes {
+            return Err(Error::dimension(format!(
+                "expected {} bytes for a {}x{} matrix, got {}",
+                nrows * row_bytes,
+                nrows,
+                ncols,
+                data.len()
+            ))
+            .into());
+        }
+        let rows = (0..nrows).map(|i| bytes_to_row(&data[i * row_bytes..(i + 1) * row_bytes], ncols));
+        Ok(Matrix::from_rows(rows, ncols))
+    }
+
+    /// The rows packed as in `from_bytes`.
+    pub fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.packed_bytes())
+    }
+
+    /// Builds a matrix from a 2-D `uint8` array or any other object exporting such a
+    /// buffer. Entries are 0/1 bytes, or, when `ncols` is given, rows packed as by
+    /// `numpy.packbits(..., axis=1, bitorder=bitorder)`. The entries are copied, so later
+    /// changes to the array do not reach the matrix.
+    #[staticmethod]
+    #[pyo3(signature = (array, ncols = None, bitorder = "big"))]
+    fn from_numpy(array: &Bound<'_, PyAny>, ncols: Option<usize>, bitorder: &str) -> PyResult<Self> {
+        numpy::from_buffer(array, ncols, BitOrder::parse(bitorder)?)
+    }
+
+    /// A new `uint8` NumPy array holding a copy of the entries as 0/1 bytes, or with
+    /// `packed=True` as rows packed like `numpy.packbits(..., axis=1, bitorder=bitorder)`.
+    /// See `packed_rows` for a view that does not copy.
+    #[pyo3(signature = (packed = false, bitorder = "big"))]
+    fn to_numpy(&self, py: Python<'_>, packed: bool, bitorder: &str) -> PyResult<PyObject> {
+        let bitorder = BitOrder::parse(bitorder)?;
+        numpy::to_numpy(py, self, packed.then_some(bitorder))
+    }
+
+    /// Read-only buffer over the rows as stored, packed like
+    /// `numpy.packbits(..., axis=1, bitorder='little')`, without copying them:
+    /// `numpy.asarray(m.packed_rows())` is a view on the matrix. Later changes to the
+    /// matrix do not reach the view.
+    fn packed_rows(&self) -> PackedRows {
+        PackedRows::new(self)
+    }
+
+    /// Writes the matrix to `path` as "matrixmarket" (coordinate), "matrixmarket-array",
+    /// "m4ri" (Sage's bracketed rows), "magma" (a `Matrix(GF(2), ...)` literal), "gf2" (the
+    /// bit-packed files of `MappedMatrix`), or as a "pbm" or "pgm" image. The format
+    /// defaults to the one matching the extension: `.mtx`, `.txt`, `.sage`, `.m`, `.mag`,
+    /// `.gf2`, `.bin`, `.pbm` or `.pgm`. Each PGM pixel covers `block x block`
+    /// entries, darker the more ones it holds.
+    #[pyo3(signature = (path, format = None, block = 1))]
+    fn save(&self, path: PathBuf, format: Option<&str>, block: usize) -> PyResult<()> {
+        let format = Format::parse(format, &path)?;
+        if block == 0 || (block > 1 && format != Format::Pgm) {
+            return Err(PyValueError::new_err("block must be 1, or positive for a PGM image"));
+        }
+        formats::save(self, &path, format, block)
+    }
+
+    /// Reads a matrix saved by `save`, or written by SciPy, Sage, M4RI, Magma, `main.rs` or
+    /// `MappedMatrix`, in any of the formats of `save` but the images.
+    #[staticmethod]
+    #[pyo3(signature = (path, format = None))]
+    fn load(path: PathBuf, format: Option<&str>) -> PyResult<Self> {
+        let format = Format::parse(format, &path)?;
+        formats::load(&path, format)
+    }
+
+    /// JSON object with the number of columns and one string of 0s and 1s per row.
+    #[cfg(feature = "serde")]
+    fn to_json(&self) -> PyResult<String> {
+        serialize::to_json(self)
+    }
+
+    #[cfg(feature = "serde")]
+    #[staticmethod]
+    fn from_json(text: &str) -> PyResult<Matrix> {
+        serialize::from_json(text)
+    }
+
+    /// Compact CBOR encoding, with the rows packed as in `to_bytes`.
+    #[cfg(feature = "serde")]
+    fn to_cbor<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        serialize::to_cbor(py, self)
+    }
+
+    #[cfg(feature = "serde")]
+    #[staticmethod]
+    fn from_cbor(data: &[u8]) -> PyResult<Matrix> {
+        serialize::from_cbor(data)
+    }
+
+    /// NumPy array protocol. Every call builds a new array, so `copy=False` is refused.
+    #[pyo3(signature = (dtype = None, copy = None))]
+    fn __array__(&self, py: Python<'_>, dtype: Option<PyObject>, copy: Option<bool>) -> PyResult<PyObject> {
+        if copy == Some(false) {
+            return Err(PyValueError::new_err("a Matrix cannot be viewed as an array without a copy"));
+        }
+        let array = numpy::to_numpy(py, self, None)?;
+        match dtype {
+            Some(dtype) => Ok(array.call_method1(py, "astype", (dtype,))?),
+            None => Ok(array),
+        }
+    }
+
+    /// Uniformly random matrix. The same `seed` gives the same matrix on every machine.
+    #[staticmethod]
+    #[pyo3(name = "random", signature = (nrows, ncols, seed = None))]
+    fn py_random(nrows: usize, ncols: usize, seed: Option<u64>) -> Self {
+        Matrix::random(nrows, ncols, &mut random::rng(seed))
+    }
+
+    /// Uniformly random matrix of rank `rank`.
+    #[staticmethod]
+    #[pyo3(name = "random_of_rank", signature = (nrows, ncols, rank, seed = None))]
+    fn py_random_of_rank(nrows: usize, ncols: usize, rank: usize, seed: Option<u64>) -> PyResult<Self> {
+        if rank > nrows.min(ncols) {
+            return Err(PyValueError::new_err(format!(
+                "a {}x{} matrix cannot have rank {}",
+                nrows, ncols, rank
+            )));
+        }
+        Ok(Matrix::random_of_rank(nrows, ncols, rank, &mut random::rng(seed)))
+    }
+
+    /// Uniformly random invertible `n x n` matrix.
+    #[staticmethod]
+    #[pyo3(name = "random_invertible", signature = (n, seed = None))]
+    fn py_random_invertible(n: usize, seed: Option<u64>) -> Self {
+        Matrix::random_invertible(n, &mut random::rng(seed))
+    }
+
+    /// Rows `start..end` as a new matrix.
+    #[pyo3(name = "get_sub_matrix")]
+    fn py_get_sub_matrix(&self, start: usize, end: usize) -> PyResult<Self> {
+        if start > end || end > self.nrows() {
+            return Err(Error::Range { start, end, len: self.nrows() }.into());
         }
+        Ok(self.get_sub_matrix(start, end))
     }
 
 
     pub fn __repr__(&self) -> String {
         let rows: Vec<String> = self
-            .elements
+            .to_list()
             .iter()
             .map(|row| format!("{:?}", row))
             .collect();
         format!("[{}]", rows.join(", "))
     }
 
-    fn to_list(&self) -> Vec<Vec<u8>> {
-        self.elements.clone()
+    pub fn to_list(&self) -> Vec<Vec<u8>> {
+        self.rows
+            .iter()
+            .map(|row| bits::unpack(row, self.ncols))
+            .collect()
     }
     //
-    fn nrows(&self) -> usize {
-        self.elements.len()
+    pub fn nrows(&self) -> usize {
+        self.rows.len()
     }
 
-    fn ncols(&self) -> usize {
-        if !self.elements.is_empty() {
-            self.elements[0].len()
-        } else {
-            0
-        }
+    pub fn ncols(&self) -> usize {
+        self.ncols
     }
 
     fn copy(&self) -> Self {
         self.clone()
     }
 
-    fn get(&self, row: usize, col: usize) -> u8 {
-        self.elements[row][col]
+    fn __copy__(&self) -> Self {
+        self.clone()
+    }
+
+    fn __deepcopy__(&self, _memo: &Bound<'_, PyAny>) -> Self {
+        self.clone()
+    }
+
+    /// Matrices are mutable, so they are compared by value but cannot be hashed.
+    #[classattr]
+    const __hash__: Option<PyObject> = None;
+
+    fn __len__(&self) -> usize {
+        self.nrows()
+    }
+
+    /// Iterates over the rows, each as a list of ints like `m[i]`.
+    fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyIterator>> {
+        let rows = (0..self.nrows())
+            .map(|i| PyList::new(py, bits::unpack(&self.rows[i], self.ncols)))
+            .collect::<PyResult<Vec<_>>>()?;
+        PyIterator::from_object(PyList::new(py, rows)?.as_any())
+    }
+
+    // Pickled as `Matrix([])` followed by `__setstate__`, for every pickle protocol.
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> (Bound<'py, PyAny>, (Vec<Vec<u8>>,), State<'py>) {
+        (slf.get_type().into_any(), (Vec::new(),), slf.borrow().__getstate__(slf.py()))
+    }
+
+    fn __getstate__<'py>(&self, py: Python<'py>) -> State<'py> {
+        (self.nrows(), self.ncols, self.to_bytes(py))
+    }
+
+    fn __setstate__(&mut self, state: (usize, usize, Vec<u8>)) -> PyResult<()> {
+        let (nrows, ncols, data) = state;
+        *self = Matrix::from_bytes(&data, nrows, ncols)?;
+        Ok(())
+    }
+
+    #[pyo3(name = "get")]
+    fn py_get(&self, row: usize, col: usize) -> PyResult<u8> {
+        error::check_index(row, self.nrows())?;
+        error::check_index(col, self.ncols)?;
+        Ok(self.get(row, col))
+    }
+
+    /// Adds row `source` to row `target`.
+    #[pyo3(name = "add_rows")]
+    fn py_add_rows(&mut self, target: usize, source: usize) -> PyResult<()> {
+        error::check_index(target, self.nrows())?;
+        error::check_index(source, self.nrows())?;
+        self.add_rows(target, source);
+        Ok(())
+    }
+
+    #[pyo3(name = "swap_rows")]
+    fn py_swap_rows(&mut self, row1: usize, row2: usize) -> PyResult<()> {
+        error::check_index(row1, self.nrows())?;
+        error::check_index(row2, self.nrows())?;
+        self.swap_rows(row1, row2);
+        Ok(())
+    }
+
+    #[pyo3(name = "is_zero_row")]
+    fn py_is_zero_row(&self, row: usize) -> PyResult<bool> {
+        error::check_index(row, self.nrows())?;
+        Ok(self.is_zero_row(row))
+    }
+
+    fn __matmul__(&self, other: PyRef<'_, Matrix>) -> PyResult<Matrix> {
+        if self.ncols() != other.nrows() {
+            return Err(Error::dimension(format!(
+                "cannot multiply a {}x{} matrix by a {}x{} matrix",
+                self.nrows(), self.ncols(), other.nrows(), other.ncols()
+            ))
+            .into());
+        }
+        Ok(self.mul(&other))
+    }
+
+    /// Product of the matrix with the column vector `v`.
+    fn mul_vec(&self, v: Vec<u8>) -> PyResult<Vec<u8>> {
+        if v.len() != self.ncols() {
+            return Err(Error::dimension(format!(
+                "vector of length {} does not match {} columns",
+                v.len(), self.ncols()
+            ))
+            .into());
+        }
+        error::check_binary(&v)?;
+        let product = self.mul_packed_vec(&bits::pack(&v, self.ncols()));
+        Ok(bits::unpack(&product, self.nrows()))
+    }
+
+    /// Entry, row or sub-matrix selected by row and column keys.
+    ///
+    /// Each key is an int, a slice or a list of ints, negative indices counting from the
+    /// end. As in NumPy, an int row key gives a row rather than a matrix: `m[i]` is row `i`
+    /// as a list of ints, the same as the rows yielded by `iter(m)`, `m[i, cols]` the
+    /// selected entries of that row and `m[i, j]` a single entry. Any other row key gives a
+    /// matrix: `m[rows]` selects rows and `m[rows, cols]` a sub-matrix.
+    fn __getitem__(&self, py: Python<'_>, key: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        let (row_key, col_key) = match key.downcast::<PyTuple>() {
+            Ok(tuple) if tuple.len() == 2 => (tuple.get_item(0)?, Some(tuple.get_item(1)?)),
+            Ok(_) => return Err(PyTypeError::new_err("expected m[rows] or m[rows, cols]")),
+            Err(_) => (key.clone(), None),
+        };
+        let rows = axis_indices(&row_key, self.nrows())?;
+        if !row_key.is_instance_of::<PyInt>() {
+            let selected = self.select_rows(&rows);
+            let m = match col_key {
+                Some(col_key) => selected.select_columns(&axis_indices(&col_key, self.ncols)?),
+                None => selected,
+            };
+            return Ok(m.into_pyobject(py)?.into_any().unbind());
+        }
+        let Some(col_key) = col_key else {
+            return Ok(PyList::new(py, bits::unpack(&self.rows[rows[0]], self.ncols))?.into_any().unbind());
+        };
+        let cols = axis_indices(&col_key, self.ncols)?;
+        if col_key.is_instance_of::<PyInt>() {
+            return Ok(self.get(rows[0], cols[0]).into_pyobject(py)?.into_any().unbind());
+        }
+        let entries: Vec<u8> = cols.iter().map(|&j| self.get(rows[0], j)).collect();
+        Ok(PyList::new(py, entries)?.into_any().unbind())
+    }
+
+    /// Sets the entry `m[i, j] = value`, negative indices counting from the end.
+    fn __setitem__(&mut self, key: (isize, isize), value: i64) -> PyResult<()> {
+        error::check_binary(&[value])?;
+        let row = normalize_index(key.0, self.nrows())?;
+        let col = normalize_index(key.1, self.ncols)?;
+        bits::set_bit(&mut self.rows[row], col, value as u8);
+        Ok(())
+    }
+
+    /// Matrix made of the given columns, in the given order.
+    #[pyo3(name = "select_columns")]
+    fn py_select_columns(&self, cols: Vec<isize>) -> PyResult<Matrix> {
+        let cols = cols
+            .into_iter()
+            .map(|j| normalize_index(j, self.ncols))
+            .collect::<PyResult<Vec<usize>>>()?;
+        Ok(self.select_columns(&cols))
+    }
+
+    /// Side-by-side concatenation of matrices with the same number of rows.
+    #[staticmethod]
+    fn hstack(matrices: Vec<PyRef<'_, Matrix>>) -> PyResult<Matrix> {
+        let (first, rest) = matrices
+            .split_first()
+            .ok_or_else(|| PyValueError::new_err("need at least one matrix"))?;
+        let mut stacked = (**first).clone();
+        for m in rest {
+            if m.nrows() != stacked.nrows() {
+                return Err(Error::dimension(format!(
+                    "cannot hstack a matrix with {} rows onto one with {} rows",
+                    m.nrows(),
+                    stacked.nrows()
+                ))
+                .into());
+            }
+            stacked = stacked.augment(m);
+        }
+        Ok(stacked)
+    }
+
+    /// Vertical concatenation of matrices with the same number of columns.
+    #[staticmethod]
+    fn vstack(matrices: Vec<PyRef<'_, Matrix>>) -> PyResult<Matrix> {
+        let (first, rest) = matrices
+            .split_first()
+            .ok_or_else(|| PyValueError::new_err("need at least one matrix"))?;
+        let mut stacked = (**first).clone();
+        for m in rest {
+            if m.ncols() != stacked.ncols() {
+                return Err(Error::dimension(format!(
+                    "cannot vstack a matrix with {} columns onto one with {} columns",
+                    m.ncols(),
+                    stacked.ncols()
+                ))
+                .into());
+            }
+            stacked.rows.extend(&m.rows);
+        }
+        Ok(stacked)
+    }
+
+    pub fn transpose(&self) -> Matrix {
+        self.transposed()
+    }
+
+    /// Kronecker product: block `(i, j)` of the result is `self[i, j] * other`.
+    pub fn kron(&self, other: PyRef<'_, Matrix>) -> Matrix {
+        let mut product = Matrix::zeros(self.nrows() * other.nrows(), self.ncols * other.ncols);
+        for (i, row) in self.rows.iter().enumerate() {
+            let mut j = bits::first_one(row);
+            while let Some(col) = j {
+                for (k, other_row) in other.rows.iter().enumerate() {
+                    bits::or_at(&mut product.rows[i * other.nrows() + k], col * other.ncols, other_row, other.ncols);
+                }
+                j = bits::first_one_from(row, col + 1);
+            }
+        }
+        product
+    }
+
+    /// Number of ones in the given row.
+    fn row_weight(&self, row: usize) -> PyResult<usize> {
+        error::check_index(row, self.nrows())?;
+        Ok(bits::popcount(&self.rows[row]))
+    }
+
+    /// Number of ones in the whole matrix.
+    fn weight(&self) -> usize {
+        self.rows.iter().map(bits::popcount).sum()
+    }
+
+    /// Reduces a matrix whose last row was just appended. With `with_transform=True`
+    /// the result also contains `U` such that `U * self` is the reduced matrix.
+    #[pyo3(name = "reduced_echelon_form_last_row", signature = (with_transform = false))]
+    fn py_reduced_echelon_form_last_row(&self, with_transform: bool) -> PyResult<EchelonForm> {
+        if self.nrows() == 0 {
+            return Err(Error::dimension("expected a matrix with at least one row").into());
+        }
+        let (reduced, operations) = self.reduced_echelon_form_last_row_tracked(with_transform);
+        Ok(EchelonForm::rows(reduced, operations))
+    }
+
+    /// Reduced row echelon form and the row operations applied. With
+    /// `with_transform=True` the result also contains `U` such that `U * self` is the
+    /// reduced matrix.
+    #[pyo3(name = "echelon_form", signature = (with_transform = false))]
+    fn py_echelon_form(&self, with_transform: bool) -> PyResult<EchelonForm> {
+        let (reduced, operations) = self.echelon_form_tracked(with_transform);
+        Ok(EchelonForm::rows(reduced, operations))
+    }
+
+    /// Same as `echelon_form`, but trivial swaps are not recorded.
+    #[pyo3(name = "row_echelon_full_matrix", signature = (with_transform = false))]
+    fn py_row_echelon_full_matrix(&self, with_transform: bool) -> PyResult<EchelonForm> {
+        let (reduced, operations) = self.row_echelon_full_matrix_tracked(with_transform);
+        Ok(EchelonForm::rows(reduced, operations))
+    }
+
+    /// Appends a row of `ncols()` entries; the first row of an empty matrix sets `ncols()`.
+    pub fn append_row(&mut self, v: Vec<u8>) -> PyResult<()> {
+        // An empty matrix without columns takes its width from its first row.
+        let shapeless = self.rows.is_empty() && self.ncols == 0;
+        if !shapeless && v.len() != self.ncols {
+            return Err(Error::dimension(format!(
+                "row of length {} does not match {} columns",
+                v.len(),
+                self.ncols
+            ))
+            .into());
+        }
+        error::check_binary(&v)?;
+        if shapeless {
+            self.ncols = v.len();
+            self.rows.set_stride(bits::words_for(self.ncols));
+        }
+        self.rows.push(&bits::pack(&v, self.ncols));
+        Ok(())
+    }
+
+    /// Appends a column of `nrows()` entries.
+    pub fn append_column(&mut self, v: Vec<u8>) -> PyResult<()> {
+        if v.len() != self.nrows() {
+            return Err(Error::dimension(format!(
+                "column of length {} does not match {} rows",
+                v.len(),
+                self.nrows()
+            ))
+            .into());
+        }
+        error::check_binary(&v)?;
+        let col = self.ncols;
+        self.ncols += 1;
+        self.rows.set_stride(bits::words_for(self.ncols));
+        for (i, row) in self.rows.iter_mut().enumerate() {
+            bits::set_bit(row, col, v[i]);
+        }
+        Ok(())
+    }
+
+    pub fn rank(&self) -> usize {
+        self.clone().rank_in_place()
+    }
+
+    /// Rank computed by reducing the matrix itself, without copying it. The matrix is
+    /// left in row echelon form.
+    pub fn rank_in_place(&mut self) -> usize {
+        if m4ri::is_large(self) {
+            let ncols = self.ncols;
+            m4ri::echelonize(self, ncols, None)
+        } else {
+            self.eliminate()
+        }
+    }
+
+    /// Rank of the rows of length `ncols` produced by `rows`, consumed one at a time
+    /// (lists of 0/1 entries). Rows are not consumed past full column rank, so
+    /// `rank_of_rows(rows, n) == n` tests full column rank without building the matrix.
+    #[staticmethod]
+    #[pyo3(name = "rank_of_rows")]
+    fn py_rank_of_rows(rows: &Bound<'_, PyAny>, ncols: usize) -> PyResult<usize> {
+        let mut failure = None;
+        let packed = rows.try_iter()?.map_while(|row| {
+            let row = row.and_then(|row| row.extract::<Vec<u8>>()).and_then(|row| {
+                if row.len() != ncols {
+                    return Err(Error::dimension(format!(
+                        "row of length {} does not match {} columns",
+                        row.len(),
+                        ncols
+                    ))
+                    .into());
+                }
+                error::check_binary(&row)?;
+                Ok(bits::pack(&row, ncols))
+            });
+            row.map_err(|err| failure = Some(err)).ok()
+        });
+        let rank = Matrix::rank_of_rows(packed, ncols);
+        failure.map_or(Ok(rank), Err)
+    }
+
+    /// Reduced column echelon form, computed as the transpose of `echelon_form` of the
+    /// transpose. The operations are column operations, recorded as row operations on
+    /// the transpose; with `with_transform=True` the result also contains `V` such that
+    /// `self * V` is the reduced matrix.
+    #[pyo3(signature = (with_transform = false))]
+    fn column_echelon_form(&self, with_transform: bool) -> EchelonForm {
+        let (reduced, operations) = self.transposed().echelon_form_tracked(with_transform);
+        EchelonForm::columns(reduced, operations)
+    }
+
+    /// Row and column rank profiles: the lexicographically first sets of linearly
+    /// independent rows and columns.
+    fn rank_profile(&self) -> (Vec<usize>, Vec<usize>) {
+        let factors = self.pluq();
+        (factors.row_perm[..factors.rank].to_vec(), self.reduced().pivot_columns())
+    }
+
+    /// Reduced row echelon form computed with the Method of Four Russians,
+    /// regardless of the matrix size.
+    #[pyo3(name = "echelon_form_m4ri", signature = (with_transform = false))]
+    fn py_echelon_form_m4ri(&self, with_transform: bool) -> PyResult<EchelonForm> {
+        let (reduced, operations) = self.echelon_form_m4ri_tracked(with_transform);
+        Ok(EchelonForm::rows(reduced, operations))
+    }
+
+    /// Inverse over GF(2); raises `ValueError` if the matrix is singular or not square.
+    #[pyo3(name = "inverse")]
+    fn py_inverse(&self) -> PyResult<Matrix> {
+        self.check_square()?;
+        self.inverse()
+            .ok_or_else(|| PyValueError::new_err("matrix is singular"))
+    }
+
+    /// Determinant over GF(2), i.e. 1 exactly when the matrix is invertible.
+    fn det(&self) -> PyResult<u8> {
+        self.check_square()?;
+        Ok((self.rank() == self.nrows()) as u8)
+    }
+
+    /// Characteristic polynomial, as its coefficients from `x^0` up to `x^n`.
+    #[pyo3(name = "charpoly")]
+    fn py_charpoly(&self) -> PyResult<Vec<u8>> {
+        self.check_square()?;
+        Ok(gf2x::coefficients(&self.charpoly()))
+    }
+
+    /// Minimal polynomial, as its coefficients from `x^0` up to the leading one.
+    #[pyo3(name = "minpoly")]
+    fn py_minpoly(&self) -> PyResult<Vec<u8>> {
+        self.check_square()?;
+        Ok(gf2x::coefficients(&self.minpoly()))
+    }
+
+    /// Invariant factors `f_1 | f_2 | ... | f_k`, each given by its coefficients as in
+    /// `charpoly`. The last one is the minimal polynomial.
+    #[pyo3(name = "invariant_factors")]
+    fn py_invariant_factors(&self) -> PyResult<Vec<Vec<u8>>> {
+        self.check_square()?;
+        Ok(self.invariant_factors().iter().map(|f| gf2x::coefficients(f)).collect())
+    }
+
+    /// Frobenius normal form: companion matrices of the invariant factors along the
+    /// diagonal, each with ones below the diagonal and the coefficients in its last column.
+    #[pyo3(name = "frobenius_form")]
+    fn py_frobenius_form(&self) -> PyResult<Matrix> {
+        self.check_square()?;
+        Ok(self.frobenius_form())
+    }
+
+    /// `self ** k` for an integer `k >= 0`, by square-and-multiply.
+    #[pyo3(name = "pow")]
+    fn py_pow(&self, k: &Bound<'_, PyInt>) -> PyResult<Matrix> {
+        self.check_square()?;
+        Ok(self.pow(&exponent_words(k)?))
+    }
+
+    fn __pow__(&self, k: &Bound<'_, PyInt>, modulo: Option<&Bound<'_, PyAny>>) -> PyResult<Matrix> {
+        if modulo.is_some() {
+            return Err(PyTypeError::new_err("pow() with a modulus is not supported for matrices"));
+        }
+        self.py_pow(k)
+    }
+
+    /// `self ** k` applied to the column vector `v`, without computing `self ** k`: `x**k`
+    /// is reduced modulo the minimal polynomial of `v` by square-and-multiply, so the cost
+    /// grows with the number of bits of `k`, and states can be jumped ahead by `2**60`
+    /// steps and more.
+    #[pyo3(name = "apply_pow")]
+    fn py_apply_pow(&self, v: Vec<u8>, k: &Bound<'_, PyInt>) -> PyResult<Vec<u8>> {
+        self.check_square()?;
+        if v.len() != self.ncols() {
+            return Err(Error::dimension(format!(
+                "vector of length {} does not match {} columns",
+                v.len(),
+                self.ncols()
+            ))
+            .into());
+        }
+        error::check_binary(&v)?;
+        let x = self.apply_pow(&bits::pack(&v, v.len()), &exponent_words(k)?);
+        Ok(bits::unpack(&x, self.nrows()))
+    }
+
+    /// Solves `self * x = b`.
+    ///
+    /// Returns `None` if the system is inconsistent, otherwise a particular solution
+    /// together with a basis of the solutions of `self * x = 0`.
+    #[pyo3(name = "solve")]
+    fn py_solve(&self, b: Vec<u8>) -> PyResult<Option<AffineSolution>> {
+        if b.len() != self.nrows() {
+            return Err(Error::dimension(format!(
+                "right-hand side of length {} does not match {} rows",
+                b.len(),
+                self.nrows()
+            ))
+            .into());
+        }
+        error::check_binary(&b)?;
+        let factors = self.pluq();
+        let unpack = |x: &Vec<u64>| bits::unpack(x, self.ncols());
+        Ok(factors.solve(&bits::pack(&b, b.len())).map(|x| {
+            let basis = factors.kernel_basis().iter().map(unpack).collect();
+            (unpack(&x), basis)
+        }))
+    }
+
+    /// Solves `self * X = B` column by column, factorising `self` once.
+    ///
+    /// Returns one entry per column of `B` (a particular solution, or `None` when that
+    /// column is inconsistent) and the shared basis of the solutions of `self * x = 0`.
+    fn solve_many(&self, b: PyRef<'_, Matrix>) -> PyResult<BatchSolution> {
+        if b.nrows() != self.nrows() {
+            return Err(Error::dimension(format!(
+                "right-hand sides have {} rows, expected {}",
+                b.nrows(),
+                self.nrows()
+            ))
+            .into());
+        }
+        let factors = self.pluq();
+        let unpack = |x: &Vec<u64>| bits::unpack(x, self.ncols());
+        let solutions = b
+            .transposed()
+            .rows
+            .iter()
+            .map(|column| factors.solve(column).as_ref().map(unpack))
+            .collect();
+        let basis = factors.kernel_basis().iter().map(unpack).collect();
+        Ok((solutions, basis))
+    }
+
+    /// PLUQ factorisation `self = P * L * U * Q`.
+    pub fn pluq(&self) -> Pluq {
+        Pluq::decompose(self)
+    }
+
+    /// Basis of the right kernel `{x : self * x = 0}`.
+    ///
+    /// The matrix does not need to be reduced beforehand. With `as_matrix=True` the basis
+    /// is returned as a `Matrix` whose rows are the kernel vectors.
+    #[pyo3(name = "kernel", signature = (as_matrix = false))]
+    fn py_kernel(&self, py: Python<'_>, as_matrix: bool) -> PyResult<PyObject> {
+        Matrix::basis_to_py(py, self.kernel_matrix(), as_matrix)
+    }
+
+    /// Basis of the left kernel `{y : y * self = 0}`, see `kernel`.
+    #[pyo3(signature = (as_matrix = false))]
+    fn left_kernel(&self, py: Python<'_>, as_matrix: bool) -> PyResult<PyObject> {
+        Matrix::basis_to_py(py, self.transposed().kernel_matrix(), as_matrix)
+    }
+
+    #[staticmethod]
+    pub fn compute_vandermonde(support: Vec<String>, monomials: Vec<String> ) -> PyResult<Vec<Vec<u8>>>{
+        error::check_binary_strings(support.iter().chain(&monomials))?;
+        let result: Vec<Vec<u8>> = support.iter()
+            .map(|zi| {
+                monomials.iter()
+                    .map(|ej| str_ops(zi, ej))
+                    .collect()
+            })
+            .collect();
+        Ok(result)
+    }
+
+    pub fn compute_next(
+        &self,
+        monom_slice: Vec<String>,
+        support_slice: Vec<String>,
+        idx: usize,
+        operations: OpLog
+    ) -> PyResult<Self> {
+        if self.nrows() != idx || self.ncols() != idx {
+            return Err(Error::dimension(format!(
+                "expected a {}x{} matrix, got {}x{}",
+                idx,
+                idx,
+                self.nrows(),
+                self.ncols()
+            ))
+            .into());
+        }
+        if monom_slice.len() <= idx || support_slice.len() <= idx {
+            return Err(Error::dimension(format!(
+                "need at least {} monomials and support points, got {} and {}",
+                idx + 1,
+                monom_slice.len(),
+                support_slice.len()
+            ))
+            .into());
+        }
+        error::check_binary_strings(monom_slice.iter().chain(&support_slice))?;
+        operations.check_rows(idx)?;
+        let mut m_copy = self.clone();
+        let row: Vec<u8> = (0..=idx)
+            .map(|i| str_ops(&support_slice[support_slice.len() - 1], &monom_slice[i]))
+            .collect();
+        let column: Vec<u8> = (0..idx)
+            .map(|i| str_ops(&support_slice[i], &monom_slice[monom_slice.len() - 1]))
+            .collect();
+
+        let n_vect: Vec<u8> = operations.apply_to_vector(column);
+        m_copy.append_column(n_vect)?;
+        m_copy.append_row(row)?;
+
+        Ok(m_copy)
+    }
+
+    pub fn construct_and_add_column(&self, support: Vec<String>, monom: String, operations: OpLog) -> PyResult<Self> {
+        if support.len() < self.nrows() {
+            return Err(Error::dimension(format!(
+                "need at least {} support points, got {}",
+                self.nrows(),
+                support.len()
+            ))
+            .into());
+        }
+        error::check_binary_strings(support.iter().chain([&monom]))?;
+        operations.check_rows(self.nrows())?;
+        let mut m_copy = self.clone();
+        let column: Vec<u8> = (0..m_copy.nrows())
+            .map(|i| str_ops(&support[i], &monom))
+            .collect();
+        let n_vect: Vec<u8> = operations.apply_to_vector(column);
+        m_copy.append_column(n_vect)?;
+
+        Ok(m_copy)
+    }
+
+    pub fn fill_rows(&self, support_slice: Vec<String>, monom_slice: Vec<String>) -> PyResult<Self> {
+        error::check_binary_strings(support_slice.iter().chain(&monom_slice))?;
+        let mut m_copy = self.clone();
+        for support in &support_slice {
+            let row: Vec<u8> = monom_slice
+                .iter()
+                .map(|monom| str_ops(support, monom))
+                .collect();
+            m_copy.append_row(row)?;
+        }
+
+        Ok(m_copy)
+    }
+
+}
+
+impl Matrix {
+    pub fn new(elements: Vec<Vec<u8>>) -> Self {
+        let ncols = elements.first().map_or(0, |row| row.len());
+        let rows = elements.iter().map(|row| bits::pack(row, ncols));
+        Matrix::from_rows(rows, ncols)
+    }
+
+    /// Matrix of packed rows, each padded with zero words or cut to `words_for(ncols)`.
+    pub(crate) fn from_rows<R: AsRef<[u64]>>(rows: impl IntoIterator<Item = R>, ncols: usize) -> Self {
+        Matrix { rows: Rows::collect(rows, bits::words_for(ncols)), ncols }
+    }
+
+    pub fn get_sub_matrix(&self, start: usize, end: usize) -> Self {
+        Self {
+            rows: self.rows.slice(start..end),
+            ncols: self.ncols,
+        }
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> u8 {
+        bits::get_bit(&self.rows[row], col)
     }
 
-    fn add_rows(&mut self, target: usize, source: usize) {
-        for i in 0..self.ncols() {
-            self.elements[target][i] ^= self.elements[source][i];
+    pub fn add_rows(&mut self, target: usize, source: usize) {
+        if target == source {
+            self.rows[target].iter_mut().for_each(|w| *w = 0);
+            return;
         }
+        let (dst, src) = self.rows.pair_mut(target, source);
+        bits::xor_into(dst, src);
     }
 
-    fn swap_rows(&mut self, row1: usize, row2: usize) {
-        self.elements.swap(row1, row2);
+    pub fn swap_rows(&mut self, row1: usize, row2: usize) {
+        self.rows.swap(row1, row2);
     }
 
-    fn is_zero_row(&self, row: usize) -> bool {
-        self.elements[row].iter().all(|&x| x == 0)
+    pub fn is_zero_row(&self, row: usize) -> bool {
+        bits::is_zero(&self.rows[row])
     }
 
-    fn reduced_echelon_form_last_row(&mut self) -> (Self, Vec<(usize, usize)>) {
+
+    pub fn echelon_form(&self) -> (Matrix, OpLog) {
+        let (m_copy, operations) = self.echelon_form_tracked(false);
+        (m_copy, operations.operations)
+    }
+
+    pub fn row_echelon_full_matrix(&self) -> (Self, OpLog) {
+        let (m_copy, operations) = self.row_echelon_full_matrix_tracked(false);
+        (m_copy, operations.operations)
+    }
+
+    fn reduced_echelon_form_last_row_tracked(&self, with_transform: bool) -> (Self, RowOps) {
         let mut m_copy = self.copy();
-        let mut last_row = m_copy.elements[m_copy.nrows() - 1].clone();
         let last_row_index = m_copy.nrows() - 1;
-        let mut operations = Vec::new();
+        let mut operations = RowOps::new(self.nrows(), with_transform);
 
-        for k in 0..m_copy.ncols() {
-            let p_index = Matrix::get_pivot(&last_row);
-            if p_index.is_none() {
+        for _ in 0..m_copy.ncols() {
+            let Some(p_index) = Matrix::get_pivot(&m_copy.rows[last_row_index]) else {
                 for j in (1..m_copy.nrows()).rev() {
                     if m_copy.is_zero_row(j) {
                         continue;
                     }
-                    let curr_pivot = Matrix::get_pivot(&m_copy.elements[j]).unwrap();
-                    let prev_pivot = Matrix::get_pivot(&m_copy.elements[j - 1]);
-                    if prev_pivot.is_none()
-                        || (!prev_pivot.is_none() && curr_pivot < prev_pivot.unwrap())
-                    {
-                        m_copy.swap_rows(j, j - 1);
-                        operations.push((j, j - 1));
-                        operations.push((j - 1, j));
-                        operations.push((j, j - 1));
-                    } else if !prev_pivot.is_none()
-                        && (prev_pivot.unwrap() == curr_pivot)
-                    {
-                        // corner case: matrix self.elements[:-1][:-1] was not in echelon form due to the last appended column
-                        m_copy.add_rows(j, j - 1);
-                        operations.push((j, j - 1));
+                    let curr_pivot = Matrix::get_pivot(&m_copy.rows[j]).unwrap();
+                    match Matrix::get_pivot(&m_copy.rows[j - 1]) {
+                        Some(prev_pivot) if prev_pivot == curr_pivot => {
+                            // corner case: matrix self.elements[:-1][:-1] was not in echelon form due to the last appended column
+                            m_copy.add_rows(j, j - 1);
+                            operations.add(j, j - 1);
+                        }
+                        Some(prev_pivot) if curr_pivot > prev_pivot => {}
+                        _ => {
+                            m_copy.swap_rows(j, j - 1);
+                            operations.swap(j, j - 1);
+                        }
                     }
                 }
                 break;
-            } else {
-                let p_index = p_index.unwrap();
-
-                let mut p_row: Option<Vec<u8>> = None;
-                let mut j_index: Option<usize> = None;
-                let mut distance_base = m_copy.nrows();
-                let mut closest: Option<usize> = None;
-                for j in 0..m_copy.nrows() - 1 {
-                    let piv: Option<usize> = Matrix::get_pivot(&m_copy.elements[j]);
-                    if piv.is_none() {
+            };
+
+            let mut j_index: Option<usize> = None;
+            let mut closest: Option<usize> = None;
+            for j in 0..m_copy.nrows() - 1 {
+                match Matrix::get_pivot(&m_copy.rows[j]) {
+                    None => {
+                        closest = Some(j);
+                        break;
+                    }
+                    Some(piv) if piv == p_index => {
+                        j_index = Some(j);
+                        break;
+                    }
+                    Some(piv) if piv > p_index => {
                         closest = Some(j);
-                        // if closest.is_none() {
-                        //     closest = Some(j);
-                        // }
                         break;
-                    } else {
-                        let piv_u = piv.unwrap();
-                        if piv_u == p_index {
-                            p_row = Some(m_copy.elements[j].clone());
-                            j_index = Some(j);
-                            break;
-                        } else {
-                            let d: isize = piv_u as isize - p_index as isize;
-                            if 0 < d {
-                                closest = Some(j);
-                                //distance_base = d as usize;
-                                break
-                            }
-                        }
                     }
+                    Some(_) => {}
                 }
+            }
 
-                if p_row.is_none() {
-                    if !closest.is_none() {
-                        let closest_u = closest.unwrap();
-                        m_copy.swap_rows(last_row_index, closest_u);
-                        last_row = m_copy.elements[last_row_index].clone();
-                        operations.push((closest_u, last_row_index));
-                        operations.push((last_row_index, closest_u));
-                        operations.push((closest_u, last_row_index));
-                    } else {
-                        for r in 0..m_copy.nrows() - 1 {
-                            let piv_r = Matrix::get_pivot(&m_copy.elements[r]);
-
-                            if m_copy.elements[r][p_index] == 1 && piv_r.unwrap() < p_index{
-                                m_copy.add_rows(r, p_index);
-                                operations.push((r, p_index));
-                            }
+            match (j_index, closest) {
+                (None, Some(closest_u)) => {
+                    m_copy.swap_rows(last_row_index, closest_u);
+                    operations.swap(closest_u, last_row_index);
+                    // The row moved up brings a new pivot: reduce it on the other pivots, then
+                    // clear its pivot column in every other row.
+                    for r in 0..m_copy.nrows() {
+                        let Some(piv_r) = Matrix::get_pivot(&m_copy.rows[r]) else {
+                            continue;
+                        };
+                        if r != closest_u && m_copy.get(closest_u, piv_r) == 1 {
+                            m_copy.add_rows(closest_u, r);
+                            operations.add(closest_u, r);
                         }
                     }
-                } else if p_row.unwrap()[p_index] == 1 {
-                    m_copy.add_rows(last_row_index, j_index.unwrap());
-                    last_row = m_copy.elements[last_row_index].clone();
-                    operations.push((last_row_index, j_index.unwrap()));
-                    let new_pivot = Matrix::get_pivot(&m_copy.elements[last_row_index]);
-                    if !new_pivot.is_none() {
-                        let new_pivot_u = new_pivot.unwrap();
-                        for r in 0..m_copy.nrows() - 1 {
-                            let piv_r = Matrix::get_pivot(&m_copy.elements[r]);
-                            if piv_r.is_none() {
-                                continue;
-                            }
-                            let piv_r_u = piv_r.unwrap();
-                            if m_copy.elements[r][new_pivot_u] == 1 && piv_r_u < new_pivot_u {
-                                m_copy.add_rows(r, last_row_index);
-                                operations.push((r, last_row_index));
-                            }
+                    for r in 0..m_copy.nrows() {
+                        if r != closest_u && m_copy.get(r, p_index) == 1 {
+                            m_copy.add_rows(r, closest_u);
+                            operations.add(r, closest_u);
                         }
                     }
                 }
+                (None, None) => {
+                    // The last row already sits below every pivot: clear its pivot column above it.
+                    for r in 0..m_copy.nrows() - 1 {
+                        let piv_r = Matrix::get_pivot(&m_copy.rows[r]);
+                        if m_copy.get(r, p_index) == 1 && piv_r.is_some_and(|p| p < p_index) {
+                            m_copy.add_rows(r, last_row_index);
+                            operations.add(r, last_row_index);
+                        }
+                    }
+                    break;
+                }
+                (Some(j_index), _) => {
+                    // The leading one is cleared here; the next pass places the rest of the row.
+                    m_copy.add_rows(last_row_index, j_index);
+                    operations.add(last_row_index, j_index);
+                }
             }
         }
 
@@ -175,31 +1070,26 @@ impl Matrix {
     }
 
 
-    pub fn echelon_form(&self) -> (Matrix, Vec<(usize, usize)>) {
+    fn echelon_form_tracked(&self, with_transform: bool) -> (Matrix, RowOps) {
+        if m4ri::is_large(self) {
+            return self.echelon_form_m4ri_tracked(with_transform);
+        }
         let mut m_copy = self.copy(); // Create a copy of the matrix
         let mut row = 0;
-        let mut operations: Vec<(usize, usize)> = Vec::new();
+        let mut operations = RowOps::new(self.nrows(), with_transform);
 
         for col in 0..self.ncols() {
-            let mut pivot_row = None;
-            for r in row..self.nrows() {
-                if m_copy.elements[r][col] == 1 {
-                    pivot_row = Some(r);
-                    break;
-                }
-            }
+            let pivot_row = (row..self.nrows()).find(|&r| m_copy.get(r, col) == 1);
 
             if let Some(pivot_row_index) = pivot_row {
                 m_copy.swap_rows(row, pivot_row_index);
-                operations.push((row, pivot_row_index));
-                operations.push((pivot_row_index, row));
-                operations.push((row, pivot_row_index));
+                operations.swap(row, pivot_row_index);
 
                 // Eliminate all other 1s in this column
                 for r in 0..self.nrows() {
-                    if r != row && m_copy.elements[r][col] == 1 {
+                    if r != row && m_copy.get(r, col) == 1 {
                         m_copy.add_rows(r, row);
-                        operations.push((r, row));
+                        operations.add(r, row);
                     }
                 }
 
@@ -211,11 +1101,14 @@ impl Matrix {
         (m_copy, operations)
     }
 
-    pub fn row_echelon_full_matrix(&self) -> (Self, Vec<(usize, usize)>) {
+    fn row_echelon_full_matrix_tracked(&self, with_transform: bool) -> (Self, RowOps) {
+        if m4ri::is_large(self) {
+            return self.echelon_form_m4ri_tracked(with_transform);
+        }
         let mut m_copy = self.clone();
         let rows = m_copy.nrows();
         let cols = m_copy.ncols();
-        let mut operations: Vec<(usize, usize)> = Vec::new();
+        let mut operations = RowOps::new(rows, with_transform);
         let mut lead = 0;
 
         for r in 0..rows {
@@ -223,7 +1116,7 @@ impl Matrix {
                 break;
             }
             let mut i = r;
-            while m_copy.elements[i][lead] == 0 {
+            while m_copy.get(i, lead) == 0 {
                 i += 1;
                 if i == rows {
                     i = r;
@@ -233,18 +1126,14 @@ impl Matrix {
                     }
                 }
             }
-            m_copy.swap_rows(r, i);
             if r != i {
-                operations.push((r, i));
-                operations.push((i, r));
-                operations.push((r, i));
+                m_copy.swap_rows(r, i);
+                operations.swap(r, i);
             }
             for i in 0..rows {
-                if i != r && m_copy.elements[i][lead] == 1 {
-                    for j in 0..cols {
-                        m_copy.elements[i][j] = (m_copy.elements[i][j] + m_copy.elements[r][j]) % 2;
-                    }
-                    operations.push((i, r));
+                if i != r && m_copy.get(i, lead) == 1 {
+                    m_copy.add_rows(i, r);
+                    operations.add(i, r);
                 }
             }
             lead += 1;
@@ -253,143 +1142,268 @@ impl Matrix {
         (m_copy, operations)
     }
 
-    fn append_row(&mut self, v: Vec<u8>) {
-        self.elements.push(v)
-    }
-
-    fn append_column(&mut self, v: Vec<u8>) {
-        for i in 0..self.nrows() {
-            self.elements[i].push(v[i]);
+    fn echelon_form_m4ri_tracked(&self, with_transform: bool) -> (Self, RowOps) {
+        let mut operations = RowOps::new(self.nrows(), false);
+        if !with_transform {
+            let mut m_copy = self.clone();
+            m4ri::echelonize(&mut m_copy, self.ncols, Some(&mut operations.operations));
+            return (m_copy, operations);
         }
+        // Reduce [A | I]; the right block ends up holding the transformation.
+        let mut augmented = self.augment(&Matrix::identity(self.nrows()));
+        m4ri::echelonize(&mut augmented, self.ncols, Some(&mut operations.operations));
+        operations.transform = Some(augmented.column_block(self.ncols, augmented.ncols));
+        (augmented.column_block(0, self.ncols), operations)
     }
 
-    pub fn rank(&self) -> usize {
-        let mut count = 0;
-        let mut pivot_columns = std::collections::HashSet::new();
-
-        for i in 0..self.nrows() {
-            let p = Matrix::get_pivot(&self.elements[i]);
-            if let Some(col) = p {
-                if pivot_columns.insert(col) {
-                    count += 1;
-                }
+    /// Rank of a stream of packed rows of length `ncols`, stopping at full column rank.
+    pub fn rank_of_rows(rows: impl IntoIterator<Item = Vec<u64>>, ncols: usize) -> usize {
+        let mut basis = RowBasis::new(ncols);
+        for row in rows {
+            basis.insert(row);
+            if basis.rank() == ncols {
+                break;
             }
         }
-        count
+        basis.rank()
     }
 
-    pub fn kernel(&self) -> Vec<Vec<u8>> {
-        let rows = self.nrows();
-        let cols = self.ncols();
-
-        let mut pivots = std::collections::HashMap::new();
-        let mut kernel_base: Vec<Vec<u8>> = Vec::new();
-        let mut free_columns: Vec<usize> = Vec::new();
-        let mut row_index = 0;
-
-        for j in 0..cols {
-            if row_index < rows && self.elements[row_index][j] == 1 {
-                pivots.insert(j, row_index);
-                row_index += 1;
-            } else {
-                free_columns.push(j);
-            }
-        }
+    /// Leading columns of the nonzero rows, in row order.
+    pub(crate) fn pivot_columns(&self) -> Vec<usize> {
+        self.rows.iter().filter_map(Matrix::get_pivot).collect()
+    }
 
-        for &free_col in &free_columns {
-            let mut kernel_vector = vec![0; cols];
-            kernel_vector[free_col] = 1;
+    /// Columns of `self` followed by the columns of `other`.
+    pub(crate) fn augment(&self, other: &Matrix) -> Self {
+        let ncols = self.ncols + other.ncols;
+        let rows = self
+            .rows
+            .iter()
+            .zip(other.rows.iter())
+            .map(|(left, right)| {
+                let mut row = left.to_vec();
+                row.resize(bits::words_for(ncols), 0);
+                bits::or_at(&mut row, self.ncols, right, other.ncols);
+                row
+            });
+        Matrix::from_rows(rows, ncols)
+    }
 
-            for (&p_index, &p_row) in &pivots {
-                let mut sum = 0;
+    /// Columns `start..end` as a new matrix.
+    pub(crate) fn column_block(&self, start: usize, end: usize) -> Self {
+        Matrix::from_rows(self.rows.iter().map(|row| bits::extract(row, start, end - start)), end - start)
+    }
 
-                for col in (0..cols).rev() {
-                    if col != p_index {
-                        sum = sum ^ (self.elements[p_row][col] * kernel_vector[col]);
-                    }
-                }
+    /// Rows packed into `ceil(ncols / 8)` bytes each, column `j` at bit `j % 8` of byte `j / 8`.
+    pub(crate) fn packed_bytes(&self) -> Vec<u8> {
+        let row_bytes = self.ncols.div_ceil(8);
+        let mut data = Vec::with_capacity(self.nrows() * row_bytes);
+        for row in &self.rows {
+            data.extend(row.iter().flat_map(|w| w.to_le_bytes()).take(row_bytes));
+        }
+        data
+    }
 
-                kernel_vector[p_index] = sum;
+    /// Matrix made of the given rows, in the given order.
+    pub(crate) fn select_rows(&self, rows: &[usize]) -> Self {
+        Matrix::from_rows(rows.iter().map(|&i| &self.rows[i]), self.ncols)
+    }
+
+    /// Matrix made of the given columns, in the given order.
+    pub(crate) fn select_columns(&self, cols: &[usize]) -> Self {
+        if let Some(&start) = cols.first() {
+            if cols.iter().enumerate().all(|(k, &j)| j == start + k) {
+                return self.column_block(start, start + cols.len());
             }
+        }
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| {
+                let mut selected = vec![0u64; bits::words_for(cols.len())];
+                for (k, &j) in cols.iter().enumerate() {
+                    bits::set_bit(&mut selected, k, bits::get_bit(row, j));
+                }
+                selected
+            });
+        Matrix::from_rows(rows, cols.len())
+    }
 
-            kernel_base.push(kernel_vector);
+    /// Inverse of a square matrix, or `None` if it is singular.
+    ///
+    /// Reduces `[A | I]` with `row_echelon_full_matrix`, so large inputs go through M4RI.
+    pub fn inverse(&self) -> Option<Matrix> {
+        let n = self.nrows();
+        let (reduced, _) = self.augment(&Matrix::identity(n)).row_echelon_full_matrix();
+        if (0..n).any(|i| Matrix::get_pivot(&reduced.rows[i]) != Some(i)) {
+            return None;
         }
+        Some(reduced.column_block(n, 2 * n))
+    }
 
-        kernel_base
+    /// Reduced row echelon form, without recording the row operations.
+    pub(crate) fn reduced(&self) -> Self {
+        if m4ri::is_large(self) {
+            let mut m_copy = self.clone();
+            m4ri::echelonize(&mut m_copy, self.ncols, None);
+            m_copy
+        } else {
+            self.row_echelon_full_matrix().0
+        }
     }
-    #[staticmethod]
-    pub fn compute_vandermonde(support: Vec<String>, monomials: Vec<String> ) -> Vec<Vec<u8>>{
-        let result: Vec<Vec<u8>> = support.iter()
-            .map(|zi| {
-                monomials.iter()
-                    .map(|ej| str_ops(&zi, &ej))
-                    .collect()
-            })
-            .collect();
-        result
+
+    pub fn kernel(&self) -> Vec<Vec<u8>> {
+        self.kernel_matrix().to_list()
     }
 
-    pub fn compute_next(
-        &self,
-        monom_slice: Vec<String>,
-        support_slice: Vec<String>,
-        idx: usize,
-        operations: Vec<(usize, usize)>
-    ) -> Self {
-        let mut m_copy = self.clone();
-        let row: Vec<u8> = (0..=idx)
-            .map(|i| str_ops(&support_slice[support_slice.len() - 1], &monom_slice[i]) as u8)
-            .collect();
-        let column: Vec<u8> = (0..idx)
-            .map(|i| str_ops(&support_slice[i], &monom_slice[monom_slice.len() - 1]) as u8)
+    /// Right kernel basis as the rows of a matrix: one vector per free column of the
+    /// reduced row echelon form, with a single 1 among the free columns.
+    pub fn kernel_matrix(&self) -> Matrix {
+        let reduced = self.reduced();
+        let pivots: Vec<(usize, usize)> = reduced
+            .rows
+            .iter()
+            .enumerate()
+            .filter_map(|(i, row)| Matrix::get_pivot(row).map(|p| (i, p)))
             .collect();
+        let mut is_pivot = vec![false; self.ncols];
+        for &(_, p) in &pivots {
+            is_pivot[p] = true;
+        }
 
-        let n_vect: Vec<u8> = apply_operations(&operations, column);
-        m_copy.append_column(n_vect);
-        m_copy.append_row(row);
+        let rows = (0..self.ncols)
+            .filter(|&j| !is_pivot[j])
+            .map(|free_col| {
+                let mut kernel_vector = vec![0u64; bits::words_for(self.ncols)];
+                bits::set_bit(&mut kernel_vector, free_col, 1);
+                for &(i, p) in &pivots {
+                    bits::set_bit(&mut kernel_vector, p, reduced.get(i, free_col));
+                }
+                kernel_vector
+            });
+        Matrix::from_rows(rows, self.ncols)
+    }
 
-        m_copy
+    fn basis_to_py(py: Python<'_>, basis: Matrix, as_matrix: bool) -> PyResult<PyObject> {
+        if as_matrix {
+            Ok(basis.into_pyobject(py)?.into_any().unbind())
+        } else {
+            Ok(basis.to_list().into_pyobject(py)?.unbind())
+        }
     }
 
-    pub fn construct_and_add_column(&self, support: Vec<String>, monom: String, operations: Vec<(usize, usize)>) -> Self {
-        let mut m_copy = self.clone();
-        let column: Vec<u8> = (0..m_copy.nrows())
-            .map(|i| str_ops(&support[i], &monom) as u8)
-            .collect();
-        let n_vect: Vec<u8> = apply_operations(&operations, column);
-        m_copy.append_column(n_vect);
+    pub(crate) fn transposed(&self) -> Self {
+        let stride = bits::words_for(self.nrows());
+        let mut words = vec![0u64; self.ncols * stride];
+        for (i, row) in self.rows.iter().enumerate() {
+            let mut j = bits::first_one(row);
+            while let Some(col) = j {
+                bits::set_bit(&mut words[col * stride..(col + 1) * stride], i, 1);
+                j = bits::first_one_from(row, col + 1);
+            }
+        }
+        Matrix { rows: Rows::from_words(words, self.ncols, stride), ncols: self.nrows() }
+    }
 
-        m_copy
+    fn check_square(&self) -> PyResult<()> {
+        if self.nrows() != self.ncols() {
+            return Err(Error::dimension(format!(
+                "expected a square matrix, got {}x{}",
+                self.nrows(),
+                self.ncols()
+            ))
+            .into());
+        }
+        Ok(())
     }
 
-    pub fn fill_rows(&self, support_slice: Vec<String>, monom_slice: Vec<String>) -> Self {
-        let mut m_copy = self.clone();
-        for j in 0..support_slice.len(){
-            let row: Vec<u8> = (0..monom_slice.len())
-                .map(|i| str_ops(&support_slice[j], &monom_slice[i]) as u8)
-                .collect();
-            m_copy.append_row(row)
+    pub(crate) fn swap_columns(&mut self, col1: usize, col2: usize) {
+        if col1 == col2 {
+            return;
+        }
+        for row in self.rows.iter_mut() {
+            let (a, b) = (bits::get_bit(row, col1), bits::get_bit(row, col2));
+            if a != b {
+                bits::set_bit(row, col1, b);
+                bits::set_bit(row, col2, a);
+            }
         }
+    }
 
-        m_copy
+    fn get_pivot(row: &[u64]) -> Option<usize> {
+        bits::first_one(row)
     }
 
+    // Forward elimination in place, returning the rank.
+    fn eliminate(&mut self) -> usize {
+        let mut rank = 0;
+        for col in 0..self.ncols {
+            if rank == self.nrows() {
+                break;
+            }
+            let Some(pivot_row) = (rank..self.nrows()).find(|&r| self.get(r, col) == 1) else {
+                continue;
+            };
+            self.swap_rows(rank, pivot_row);
+            for r in rank + 1..self.nrows() {
+                if self.get(r, col) == 1 {
+                    self.add_rows(r, rank);
+                }
+            }
+            rank += 1;
+        }
+        rank
+    }
 }
 
-impl Matrix {
-    fn get_pivot(row: &Vec<u8>) -> Option<usize> {
-        row.iter().position(|&x| x == 1)
+
+// Packs little-endian bytes into a row of `ncols` bits, dropping the bits past `ncols`.
+pub(crate) fn bytes_to_row(bytes: &[u8], ncols: usize) -> Vec<u64> {
+    let row: Vec<u64> = bytes
+        .chunks(8)
+        .map(|chunk| chunk.iter().rev().fold(0u64, |acc, &b| (acc << 8) | b as u64))
+        .collect();
+    bits::truncate(&row, ncols)
+}
+
+// Little-endian words of a non-negative Python int.
+fn exponent_words(k: &Bound<'_, PyInt>) -> PyResult<Vec<u64>> {
+    if k.lt(0)? {
+        return Err(PyValueError::new_err(format!("exponent must be non-negative, got {}", k)));
     }
+    let nbits: usize = k.call_method0("bit_length")?.extract()?;
+    let bytes: Vec<u8> = k.call_method1("to_bytes", (nbits.div_ceil(8), "little"))?.extract()?;
+    Ok(bytes_to_row(&bytes, nbits))
 }
 
+fn normalize_index(index: isize, len: usize) -> PyResult<usize> {
+    let i = if index < 0 { index + len as isize } else { index };
+    if i < 0 || i >= len as isize {
+        return Err(Error::Index { index, len }.into());
+    }
+    Ok(i as usize)
+}
 
-fn add_to_vectors(mut v1: Vec<u8>, v2: Vec<u8>, size: usize) -> Vec<u8> {
-    for i in 0..size {
-        v1[i] ^= v2[i];
+// Indices selected along an axis of length `len` by an int, a slice or a list of ints.
+fn axis_indices(key: &Bound<'_, PyAny>, len: usize) -> PyResult<Vec<usize>> {
+    if let Ok(slice) = key.downcast::<PySlice>() {
+        let indices = slice.indices(len as isize)?;
+        return Ok((0..indices.slicelength)
+            .map(|k| (indices.start + k as isize * indices.step) as usize)
+            .collect());
+    }
+    if let Ok(index) = key.extract::<isize>() {
+        return Ok(vec![normalize_index(index, len)?]);
+    }
+    if let Ok(list) = key.extract::<Vec<isize>>() {
+        return list.into_iter().map(|index| normalize_index(index, len)).collect();
     }
-    v1
+    Err(PyTypeError::new_err("indices must be integers, slices or lists of integers"))
 }
 
+/// Evaluates the monomial `s2` at the point `s1`, both given as strings of 0s and 1s.
+///
+/// Panics on other characters: Python entry points check their strings first.
 pub fn str_ops(s1: &str, s2: &str) -> u8 {
     s1.chars()
         .zip(s2.chars())
@@ -401,13 +1415,6 @@ pub fn str_ops(s1: &str, s2: &str) -> u8 {
         .product()
 }
 
-fn apply_operations(operations: &Vec<(usize, usize)>, v: Vec<u8>) -> Vec<u8> {
-    let mut result = v.clone();
-    for &(op1, op2) in operations.iter() {
-        result[op1] = (result[op1] + result[op2]) % 2;
-    }
-    result
-}
 
 
 fn is_submonomial(sub_monom: &str, monom: &str) -> bool {
@@ -421,7 +1428,36 @@ fn is_submonomial(sub_monom: &str, monom: &str) -> bool {
     true
 }
 
+/// Checks that the ANF `g` over the monomials `mapping` vanishes on every point of `z`,
+/// returning the first point where it does not.
+#[pyfunction]
+#[pyo3(name = "verify")]
+pub fn py_verify(z: Vec<String>, g: Vec<u8>, mapping: Vec<String>) -> PyResult<(bool, Option<(usize, String)>)> {
+    check_anf(&z, &g, &mapping)?;
+    Ok(verify(z, g, mapping))
+}
+
+/// Same as `verify`.
 #[pyfunction]
+#[pyo3(name = "verify_2")]
+pub fn py_verify_2(z: Vec<String>, g: Vec<u8>, mapping: Vec<String>) -> PyResult<(bool, Option<(usize, String)>)> {
+    check_anf(&z, &g, &mapping)?;
+    Ok(verify_2(z, g, mapping))
+}
+
+// `g` holds one coefficient per monomial of `mapping`, possibly leaving the last ones out.
+fn check_anf(z: &[String], g: &[u8], mapping: &[String]) -> Result<(), Error> {
+    if g.len() > mapping.len() {
+        return Err(Error::dimension(format!(
+            "{} coefficients for {} monomials",
+            g.len(),
+            mapping.len()
+        )));
+    }
+    error::check_binary(g)?;
+    error::check_binary_strings(z.iter().chain(mapping))
+}
+
 pub fn verify(z: Vec<String>, g: Vec<u8>, mapping: Vec<String>) -> (bool, Option<(usize, String)>) {
     for (idx, item) in z.iter().enumerate() {
         let anf: Vec<u8> = (0..g.len())
@@ -436,7 +1472,6 @@ pub fn verify(z: Vec<String>, g: Vec<u8>, mapping: Vec<String>) -> (bool, Option
     (true, None)
 }
 
-#[pyfunction]
 pub fn verify_2(z: Vec<String>, g: Vec<u8>, mapping: Vec<String>) -> (bool, Option<(usize, String)>) {
     for (idx, item) in z.iter().enumerate() {
         let sum: u8 = g.iter()
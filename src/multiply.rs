@@ -0,0 +1,184 @@
+// Matrix products over GF(2).
+//
+// Small products use the Method of Four Russians for multiplication (M4RM): the rows of
+// `b` are grouped `k` at a time, all their combinations are tabulated, and each row of
+// `a` picks the matching combination with one lookup per group. Large square-ish
+// products recurse with the Strassen-Winograd schedule (7 products, 15 additions) on
+// word-aligned quadrants until the blocks drop below `STRASSEN_CUTOFF`.
+use rayon::prelude::*;
+use crate::bits;
+use crate::m4ri;
+use crate::matrix::Matrix;
+use crate::rows::Rows;
+
+/// Products whose three dimensions are all at least this large use Strassen-Winograd.
+pub const STRASSEN_CUTOFF: usize = 1024;
+
+const M4RM_K: usize = 8;
+
+impl Matrix {
+    /// Product `self * other` over GF(2).
+    ///
+    /// Panics if `self.ncols() != other.nrows()`.
+    pub fn mul(&self, other: &Matrix) -> Matrix {
+        assert_eq!(
+            self.ncols(),
+            other.nrows(),
+            "cannot multiply a {}x{} matrix by a {}x{} matrix",
+            self.nrows(),
+            self.ncols(),
+            other.nrows(),
+            other.ncols()
+        );
+        if self.nrows() < bits::WORD_BITS {
+            naive(self, other)
+        } else if use_strassen(self, other) {
+            strassen(self, other)
+        } else {
+            m4rm(self, other)
+        }
+    }
+
+    /// Matrix-vector product `self * v` for a packed vector `v` of length `self.ncols()`.
+    pub fn mul_packed_vec(&self, v: &[u64]) -> Vec<u64> {
+        let mut out = vec![0u64; bits::words_for(self.nrows())];
+        for (i, row) in self.rows.iter().enumerate() {
+            bits::set_bit(&mut out, i, bits::dot(row, v));
+        }
+        out
+    }
+
+    fn xor(&self, other: &Matrix) -> Matrix {
+        let mut out = self.clone();
+        for (dst, src) in out.rows.iter_mut().zip(other.rows.iter()) {
+            bits::xor_into(dst, src);
+        }
+        out
+    }
+
+    /// Copy of `self` resized to `nrows x ncols`, cropping or padding with zeros.
+    fn padded(&self, nrows: usize, ncols: usize) -> Matrix {
+        let cropped = self.rows.iter().take(nrows).map(|row| bits::truncate(row, ncols));
+        let mut rows = Rows::collect(cropped, bits::words_for(ncols));
+        rows.resize(nrows);
+        Matrix { rows, ncols }
+    }
+
+    // Requires an even number of rows and an even number of whole words per row.
+    fn quadrants(&self) -> [Matrix; 4] {
+        let half_rows = self.nrows() / 2;
+        let half_words = self.rows.stride() / 2;
+        let half_cols = half_words * bits::WORD_BITS;
+        let block = |first: usize, left: bool| Matrix {
+            rows: Rows::collect(
+                self.rows
+                    .iter()
+                    .skip(first)
+                    .take(half_rows)
+                    .map(|row| if left { &row[..half_words] } else { &row[half_words..] }),
+                half_words,
+            ),
+            ncols: half_cols,
+        };
+        [block(0, true), block(0, false), block(half_rows, true), block(half_rows, false)]
+    }
+
+    fn from_quadrants(c11: &Matrix, c12: &Matrix, c21: &Matrix, c22: &Matrix) -> Matrix {
+        let stride = c11.rows.stride() + c12.rows.stride();
+        let join = |words: &mut Vec<u64>, left: &Matrix, right: &Matrix| {
+            for (l, r) in left.rows.iter().zip(right.rows.iter()) {
+                words.extend_from_slice(l);
+                words.extend_from_slice(r);
+            }
+        };
+        let mut words = Vec::with_capacity((c11.nrows() + c21.nrows()) * stride);
+        join(&mut words, c11, c12);
+        join(&mut words, c21, c22);
+        Matrix { rows: Rows::from_words(words, c11.nrows() + c21.nrows(), stride), ncols: c11.ncols() + c12.ncols() }
+    }
+}
+
+fn use_strassen(a: &Matrix, b: &Matrix) -> bool {
+    a.nrows() >= STRASSEN_CUTOFF && a.ncols() >= STRASSEN_CUTOFF && b.ncols() >= STRASSEN_CUTOFF
+}
+
+/// Reference product: every set bit `a[i][j]` adds row `j` of `b` to row `i` of the result.
+pub fn naive(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut c = Matrix::zeros(a.nrows(), b.ncols());
+    for (c_row, a_row) in c.rows.iter_mut().zip(a.rows.iter()) {
+        for j in (0..a.ncols()).filter(|&j| bits::get_bit(a_row, j) == 1) {
+            bits::xor_into(c_row, &b.rows[j]);
+        }
+    }
+    c
+}
+
+pub fn m4rm(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut c = Matrix::zeros(a.nrows(), b.ncols());
+    let width = bits::words_for(b.ncols());
+    if width == 0 {
+        return c;
+    }
+
+    for start in (0..a.ncols()).step_by(M4RM_K) {
+        let kk = M4RM_K.min(a.ncols() - start);
+        let table = m4ri::gray_table(b, start, kk, 0);
+        c.rows
+            .par_iter_mut()
+            .zip(a.rows.par_iter())
+            .for_each(|(c_row, a_row)| {
+                let idx = bits::read_bits(a_row, start, kk) as usize;
+                if idx != 0 {
+                    bits::xor_into(c_row, &table[idx * width..(idx + 1) * width]);
+                }
+            });
+    }
+    c
+}
+
+fn strassen(a: &Matrix, b: &Matrix) -> Matrix {
+    if !use_strassen(a, b) {
+        return m4rm(a, b);
+    }
+
+    // Pad every dimension to a multiple of two words so the quadrants are word-aligned.
+    let align = 2 * bits::WORD_BITS;
+    let (m, n, p) = (a.nrows(), a.ncols(), b.ncols());
+    let (mp, np, pp) = (m.next_multiple_of(align), n.next_multiple_of(align), p.next_multiple_of(align));
+    let a = a.padded(mp, np);
+    let b = b.padded(np, pp);
+
+    let [a11, a12, a21, a22] = a.quadrants();
+    let [b11, b12, b21, b22] = b.quadrants();
+
+    let s1 = a21.xor(&a22);
+    let s2 = s1.xor(&a11);
+    let s3 = a11.xor(&a21);
+    let s4 = a12.xor(&s2);
+    let t1 = b12.xor(&b11);
+    let t2 = b22.xor(&t1);
+    let t3 = b22.xor(&b12);
+    let t4 = t2.xor(&b21);
+
+    let pairs = [
+        (&a11, &b11),
+        (&a12, &b21),
+        (&s4, &b22),
+        (&a22, &t4),
+        (&s1, &t1),
+        (&s2, &t2),
+        (&s3, &t3),
+    ];
+    let products: Vec<Matrix> = pairs.par_iter().map(|(x, y)| strassen(x, y)).collect();
+    let [p1, p2, p3, p4, p5, p6, p7]: [Matrix; 7] = products.try_into().ok().unwrap();
+
+    let u1 = p1.xor(&p2);
+    let u2 = p1.xor(&p6);
+    let u3 = u2.xor(&p7);
+    let u4 = u2.xor(&p5);
+    let u5 = u4.xor(&p3);
+    let u6 = u3.xor(&p4);
+    let u7 = u3.xor(&p5);
+
+    Matrix::from_quadrants(&u1, &u5, &u6, &u7).padded(m, p)
+}
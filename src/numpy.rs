@@ -0,0 +1,181 @@
+// NumPy interop through the buffer protocol, without linking against NumPy.
+//
+// Arrays are read with `PyBuffer`, so any object exporting a 2-D `uint8` buffer works
+// (NumPy arrays, `memoryview`s, ...), contiguous or strided. They are copied once out of
+// the buffer and packed into words, since the matrix owns its rows.
+//
+// The rows of a `Matrix` sit in one buffer of little-endian words, which is exactly an
+// array of rows packed with `bitorder='little'`, each padded to a whole number of words.
+// `PackedRows` exports that buffer read-only without copying it. `to_numpy` builds new
+// arrays instead, over a `bytearray` filled here, so NumPy is only imported on output.
+use std::ffi::{c_int, c_void};
+use std::ptr;
+use std::sync::Arc;
+use pyo3::buffer::PyBuffer;
+use pyo3::exceptions::{PyBufferError, PyValueError};
+use pyo3::ffi;
+use pyo3::prelude::*;
+use pyo3::types::PyByteArray;
+use crate::bits;
+use crate::error::{self, Error};
+use crate::matrix::Matrix;
+
+/// Bit order of packed rows, as in `numpy.packbits`.
+#[derive(Clone, Copy)]
+pub enum BitOrder {
+    /// Column `8 * b` is the most significant bit of byte `b` (NumPy's default).
+    Big,
+    /// Column `8 * b` is the least significant bit of byte `b`.
+    Little,
+}
+
+impl BitOrder {
+    pub fn parse(bitorder: &str) -> PyResult<Self> {
+        match bitorder {
+            "big" => Ok(BitOrder::Big),
+            "little" => Ok(BitOrder::Little),
+            _ => Err(PyValueError::new_err(format!(
+                "bitorder must be 'big' or 'little', got {:?}",
+                bitorder
+            ))),
+        }
+    }
+
+    // Converts a byte between this bit order and the little one, in either direction.
+    fn reorder(self, byte: u8) -> u8 {
+        match self {
+            BitOrder::Big => byte.reverse_bits(),
+            BitOrder::Little => byte,
+        }
+    }
+}
+
+/// Reads a 2-D `uint8` buffer: one 0/1 byte per entry when `ncols` is `None`, otherwise
+/// rows of `ceil(ncols / 8)` packed bytes in the given bit order.
+pub fn from_buffer(obj: &Bound<'_, PyAny>, ncols: Option<usize>, bitorder: BitOrder) -> PyResult<Matrix> {
+    let buffer = PyBuffer::<u8>::get(obj)?;
+    let &[nrows, width] = buffer.shape() else {
+        return Err(Error::dimension(format!(
+            "expected a 2-D array, got {} dimensions",
+            buffer.dimensions()
+        ))
+        .into());
+    };
+    let data = buffer.to_vec(obj.py())?;
+
+    let Some(ncols) = ncols else {
+        error::check_binary(&data)?;
+        let rows = data.chunks(width.max(1)).take(nrows).map(|row| bits::pack(row, width));
+        let mut m = Matrix::from_rows(rows, width);
+        m.rows.resize(nrows);
+        return Ok(m);
+    };
+
+    if width != ncols.div_ceil(8) {
+        return Err(Error::dimension(format!(
+            "packed rows of {} bytes do not hold {} columns",
+            width, ncols
+        ))
+        .into());
+    }
+    let mut data = data;
+    data.iter_mut().for_each(|b| *b = bitorder.reorder(*b));
+    Matrix::from_bytes(&data, nrows, ncols)
+}
+
+/// `m` as a `uint8` NumPy array, unpacked or with packed rows in the given bit order.
+pub fn to_numpy(py: Python<'_>, m: &Matrix, packed: Option<BitOrder>) -> PyResult<PyObject> {
+    let width = match packed {
+        None => m.ncols(),
+        Some(_) => m.ncols().div_ceil(8),
+    };
+    let buffer = PyByteArray::new_with(py, m.nrows() * width, |data| {
+        for (row, out) in m.rows.iter().zip(data.chunks_exact_mut(width.max(1))) {
+            match packed {
+                None => out.copy_from_slice(&bits::unpack(row, width)),
+                Some(bitorder) => {
+                    let bytes = row.iter().flat_map(|word| word.to_le_bytes());
+                    for (o, b) in out.iter_mut().zip(bytes) {
+                        *o = bitorder.reorder(b);
+                    }
+                }
+            }
+        }
+        Ok(())
+    })?;
+    let array = py
+        .import("numpy")?
+        .call_method1("frombuffer", (buffer, "uint8"))?
+        .call_method1("reshape", ((m.nrows(), width),))?;
+    Ok(array.unbind())
+}
+
+/// Read-only `uint8` buffer over the rows of a matrix, packed as by
+/// `numpy.packbits(..., axis=1, bitorder='little')` and shared with the matrix rather than
+/// copied. Writing to the matrix afterwards leaves the view as it was.
+#[pyclass(frozen, module = "algebraic_immunity_utils")]
+pub struct PackedRows {
+    words: Arc<Vec<u64>>,
+    shape: [ffi::Py_ssize_t; 2],
+    strides: [ffi::Py_ssize_t; 2],
+}
+
+impl PackedRows {
+    pub fn new(m: &Matrix) -> Self {
+        let words = if cfg!(target_endian = "little") {
+            Arc::clone(m.rows.words())
+        } else {
+            Arc::new(m.rows.words().iter().map(|word| word.to_le()).collect())
+        };
+        PackedRows {
+            words,
+            shape: [m.nrows() as isize, m.ncols().div_ceil(8) as isize],
+            strides: [(m.rows.stride() * 8) as isize, 1],
+        }
+    }
+}
+
+#[pymethods]
+impl PackedRows {
+    unsafe fn __getbuffer__(slf: Bound<'_, Self>, view: *mut ffi::Py_buffer, flags: c_int) -> PyResult<()> {
+        if view.is_null() {
+            return Err(PyBufferError::new_err("view is null"));
+        }
+        if flags & ffi::PyBUF_WRITABLE == ffi::PyBUF_WRITABLE {
+            return Err(PyBufferError::new_err("packed rows are read-only"));
+        }
+        let this = slf.get();
+        let contiguous = this.shape[0] <= 1 || this.strides[0] == this.shape[1];
+        if flags & ffi::PyBUF_STRIDES != ffi::PyBUF_STRIDES && !contiguous {
+            return Err(PyBufferError::new_err("rows are padded to whole words and need strides"));
+        }
+
+        // SAFETY: `view` is a buffer to fill, handed over by the caller. It keeps a
+        // reference to `slf`, whose words and shape never change and outlive the view.
+        let view = &mut *view;
+        view.buf = this.words.as_ptr() as *mut c_void;
+        view.obj = slf.clone().into_any().into_ptr();
+        view.len = this.shape[0] * this.shape[1];
+        view.readonly = 1;
+        view.itemsize = 1;
+        view.format = if flags & ffi::PyBUF_FORMAT == ffi::PyBUF_FORMAT {
+            c"B".as_ptr() as *mut _
+        } else {
+            ptr::null_mut()
+        };
+        view.ndim = 2;
+        view.shape = if flags & ffi::PyBUF_ND == ffi::PyBUF_ND {
+            this.shape.as_ptr() as *mut _
+        } else {
+            ptr::null_mut()
+        };
+        view.strides = if flags & ffi::PyBUF_STRIDES == ffi::PyBUF_STRIDES {
+            this.strides.as_ptr() as *mut _
+        } else {
+            ptr::null_mut()
+        };
+        view.suboffsets = ptr::null_mut();
+        view.internal = ptr::null_mut();
+        Ok(())
+    }
+}
@@ -0,0 +1,253 @@
+use pyo3::prelude::*;
+use crate::error::Error;
+use crate::matrix::Matrix;
+#[cfg(feature = "serde")]
+use crate::serialize;
+
+/// An elementary row operation over GF(2).
+#[pyclass(eq, frozen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RowOp {
+    /// Exchange rows `row1` and `row2`.
+    Swap { row1: usize, row2: usize },
+    /// Add (XOR) row `source` to row `target`.
+    AddRow { target: usize, source: usize },
+}
+
+#[pymethods]
+impl RowOp {
+    pub fn __repr__(&self) -> String {
+        match self {
+            RowOp::Swap { row1, row2 } => format!("Swap({}, {})", row1, row2),
+            RowOp::AddRow { target, source } => format!("AddRow({}, {})", target, source),
+        }
+    }
+}
+
+impl RowOp {
+    // Two swaps of the same pair of rows, or two identical additions, cancel out.
+    fn cancels(&self, other: &RowOp) -> bool {
+        match (*self, *other) {
+            (RowOp::Swap { row1: a, row2: b }, RowOp::Swap { row1: c, row2: d }) => {
+                (a, b) == (c, d) || (a, b) == (d, c)
+            }
+            (RowOp::AddRow { target: a, source: b }, RowOp::AddRow { target: c, source: d }) => {
+                a != b && (a, b) == (c, d)
+            }
+            _ => false,
+        }
+    }
+
+    fn is_identity(&self) -> bool {
+        matches!(*self, RowOp::Swap { row1, row2 } if row1 == row2)
+    }
+
+    fn max_row(&self) -> usize {
+        match *self {
+            RowOp::Swap { row1, row2 } => row1.max(row2),
+            RowOp::AddRow { target, source } => target.max(source),
+        }
+    }
+}
+
+/// Ordered log of row operations, as recorded by the elimination routines of `Matrix`.
+///
+/// Applying the log to a matrix `A` replays the elimination: the result of
+/// `log.apply_to_matrix(A)` is the reduced matrix.
+#[pyclass(eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OpLog {
+    pub ops: Vec<RowOp>,
+}
+
+#[pymethods]
+impl OpLog {
+    #[new]
+    #[pyo3(signature = (ops = None))]
+    pub fn new(ops: Option<Vec<RowOp>>) -> Self {
+        OpLog { ops: ops.unwrap_or_default() }
+    }
+
+    /// Builds a log from `(target, source)` row additions, the encoding used before
+    /// `RowOp` existed (a swap being three additions).
+    #[staticmethod]
+    pub fn from_pairs(pairs: Vec<(usize, usize)>) -> Self {
+        OpLog {
+            ops: pairs
+                .into_iter()
+                .map(|(target, source)| RowOp::AddRow { target, source })
+                .collect(),
+        }
+    }
+
+    /// The log in the `(target, source)` encoding, each swap expanded into three additions.
+    pub fn to_pairs(&self) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::with_capacity(self.ops.len());
+        for op in &self.ops {
+            match *op {
+                RowOp::Swap { row1, row2 } => {
+                    pairs.push((row1, row2));
+                    pairs.push((row2, row1));
+                    pairs.push((row1, row2));
+                }
+                RowOp::AddRow { target, source } => pairs.push((target, source)),
+            }
+        }
+        pairs
+    }
+
+    pub fn to_list(&self) -> Vec<RowOp> {
+        self.ops.clone()
+    }
+
+    pub fn push(&mut self, op: RowOp) {
+        self.ops.push(op);
+    }
+
+    pub fn swap(&mut self, row1: usize, row2: usize) {
+        self.ops.push(RowOp::Swap { row1, row2 });
+    }
+
+    pub fn add_row(&mut self, target: usize, source: usize) {
+        self.ops.push(RowOp::AddRow { target, source });
+    }
+
+    /// Appends the operations of `other` to this log.
+    pub fn extend(&mut self, other: &OpLog) {
+        self.ops.extend_from_slice(&other.ops);
+    }
+
+    /// The log that applies `self` first and then `other`.
+    pub fn compose(&self, other: &OpLog) -> OpLog {
+        let mut composed = self.clone();
+        composed.extend(other);
+        composed
+    }
+
+    /// The log undoing `self`. Every operation is an involution, so this is the
+    /// reversed log; it is only a true inverse if no `AddRow` adds a row to itself.
+    pub fn inverse(&self) -> OpLog {
+        OpLog { ops: self.ops.iter().rev().copied().collect() }
+    }
+
+    /// Equivalent shorter log: trivial swaps are dropped, adjacent operations that
+    /// cancel are removed, and additions of the form `(a, b), (b, a), (a, b)` become swaps.
+    pub fn compact(&self) -> OpLog {
+        let mut ops: Vec<RowOp> = Vec::with_capacity(self.ops.len());
+        for op in self.ops.iter().filter(|op| !op.is_identity()) {
+            if ops.last().is_some_and(|last| last.cancels(op)) {
+                ops.pop();
+                continue;
+            }
+            ops.push(*op);
+            if let [.., RowOp::AddRow { target: a, source: b }, RowOp::AddRow { target: c, source: d }, RowOp::AddRow { target: e, source: f }] = ops[..] {
+                if a != b && (a, b) == (d, c) && (a, b) == (e, f) {
+                    ops.truncate(ops.len() - 3);
+                    ops.push(RowOp::Swap { row1: a, row2: b });
+                }
+            }
+        }
+        OpLog { ops }
+    }
+
+    /// Applies the log to the column vector `v`.
+    #[pyo3(name = "apply_to_vector")]
+    fn py_apply_to_vector(&self, v: Vec<u8>) -> PyResult<Vec<u8>> {
+        self.check_rows(v.len())?;
+        Ok(self.apply_to_vector(v))
+    }
+
+    /// Applies the log to the rows of `m`, returning a new matrix.
+    #[pyo3(name = "apply_to_matrix")]
+    fn py_apply_to_matrix(&self, m: PyRef<'_, Matrix>) -> PyResult<Matrix> {
+        self.check_rows(m.nrows())?;
+        let mut result = m.clone();
+        self.apply_to_matrix(&mut result);
+        Ok(result)
+    }
+
+    /// The `nrows x nrows` matrix `U` with `U * A == log.apply_to_matrix(A)`.
+    pub fn transform(&self, nrows: usize) -> PyResult<Matrix> {
+        self.check_rows(nrows)?;
+        let mut u = Matrix::identity(nrows);
+        self.apply_to_matrix(&mut u);
+        Ok(u)
+    }
+
+    pub fn __len__(&self) -> usize {
+        self.ops.len()
+    }
+
+    pub fn __getitem__(&self, index: isize) -> PyResult<RowOp> {
+        let len = self.ops.len() as isize;
+        let i = if index < 0 { index + len } else { index };
+        if i < 0 || i >= len {
+            return Err(Error::Index { index, len: self.ops.len() }.into());
+        }
+        Ok(self.ops[i as usize])
+    }
+
+    pub fn __repr__(&self) -> String {
+        let ops: Vec<String> = self.ops.iter().map(|op| op.__repr__()).collect();
+        format!("OpLog([{}])", ops.join(", "))
+    }
+
+    /// JSON list of the operations, such as `{"Swap": {"row1": 0, "row2": 2}}`.
+    #[cfg(feature = "serde")]
+    fn to_json(&self) -> PyResult<String> {
+        serialize::to_json(self)
+    }
+
+    #[cfg(feature = "serde")]
+    #[staticmethod]
+    fn from_json(text: &str) -> PyResult<OpLog> {
+        serialize::from_json(text)
+    }
+
+    /// Compact CBOR encoding of `to_json`.
+    #[cfg(feature = "serde")]
+    fn to_cbor<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyBytes>> {
+        serialize::to_cbor(py, self)
+    }
+
+    #[cfg(feature = "serde")]
+    #[staticmethod]
+    fn from_cbor(data: &[u8]) -> PyResult<OpLog> {
+        serialize::from_cbor(data)
+    }
+}
+
+impl OpLog {
+    pub fn apply_to_vector(&self, v: Vec<u8>) -> Vec<u8> {
+        let mut result = v;
+        for op in &self.ops {
+            match *op {
+                RowOp::Swap { row1, row2 } => result.swap(row1, row2),
+                RowOp::AddRow { target, source } => result[target] ^= result[source],
+            }
+        }
+        result
+    }
+
+    pub fn apply_to_matrix(&self, m: &mut Matrix) {
+        for op in &self.ops {
+            match *op {
+                RowOp::Swap { row1, row2 } => m.swap_rows(row1, row2),
+                RowOp::AddRow { target, source } => m.add_rows(target, source),
+            }
+        }
+    }
+
+    pub(crate) fn check_rows(&self, nrows: usize) -> PyResult<()> {
+        match self.ops.iter().map(RowOp::max_row).max() {
+            Some(max_row) if max_row >= nrows => Err(Error::dimension(format!(
+                "log touches row {} but the target has only {} rows",
+                max_row, nrows
+            ))
+            .into()),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use pyo3::prelude::*;
+use crate::bits;
+use crate::error::{self, Error};
+use crate::matrix::Matrix;
+#[cfg(feature = "serde")]
+use crate::serialize;
+
+/// PLUQ factorisation `A = P * L * U * Q` of a GF(2) matrix of rank `r`.
+///
+/// `L` is `nrows x r` unit lower trapezoidal and `U` is `r x ncols` upper trapezoidal
+/// with a unit diagonal. Pivots are searched row by row, so the first `r` entries of
+/// `row_perm` are the row rank profile of `A`.
+#[pyclass(name = "PLUQ")]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pluq {
+    /// `row_perm[i]` is the row of `A` moved to position `i`.
+    #[pyo3(get)]
+    pub row_perm: Vec<usize>,
+    /// `col_perm[j]` is the column of `A` moved to position `j`.
+    #[pyo3(get)]
+    pub col_perm: Vec<usize>,
+    #[pyo3(get)]
+    pub l: Matrix,
+    #[pyo3(get)]
+    pub u: Matrix,
+    #[pyo3(get)]
+    pub rank: usize,
+}
+
+#[pymethods]
+impl Pluq {
+    /// Row permutation matrix `P`.
+    fn p(&self) -> Matrix {
+        let mut p = Matrix::zeros(self.row_perm.len(), self.row_perm.len());
+        for (i, &row) in self.row_perm.iter().enumerate() {
+            bits::set_bit(&mut p.rows[row], i, 1);
+        }
+        p
+    }
+
+    /// Column permutation matrix `Q`.
+    fn q(&self) -> Matrix {
+        let mut q = Matrix::zeros(self.col_perm.len(), self.col_perm.len());
+        for (j, &col) in self.col_perm.iter().enumerate() {
+            bits::set_bit(&mut q.rows[j], col, 1);
+        }
+        q
+    }
+
+    /// Indices of the first linearly independent rows of `A`, in increasing order.
+    fn row_rank_profile(&self) -> Vec<usize> {
+        self.row_perm[..self.rank].to_vec()
+    }
+
+    /// A solution `x` of `A x = b`, or `None` if the system is inconsistent.
+    #[pyo3(name = "solve")]
+    fn py_solve(&self, b: Vec<u8>) -> PyResult<Option<Vec<u8>>> {
+        if b.len() != self.row_perm.len() {
+            return Err(Error::dimension(format!(
+                "right-hand side of length {} does not match {} rows",
+                b.len(),
+                self.row_perm.len()
+            ))
+            .into());
+        }
+        error::check_binary(&b)?;
+        let x = self.solve(&bits::pack(&b, b.len()));
+        Ok(x.map(|x| bits::unpack(&x, self.col_perm.len())))
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "PLUQ(rank={}, row_perm={:?}, col_perm={:?})",
+            self.rank, self.row_perm, self.col_perm
+        )
+    }
+
+    /// JSON object with every field, matrices encoded as by `Matrix.to_json`.
+    #[cfg(feature = "serde")]
+    fn to_json(&self) -> PyResult<String> {
+        serialize::to_json(self)
+    }
+
+    #[cfg(feature = "serde")]
+    #[staticmethod]
+    fn from_json(text: &str) -> PyResult<Pluq> {
+        serialize::from_json(text)
+    }
+
+    /// Compact CBOR encoding, matrices encoded as by `Matrix.to_cbor`.
+    #[cfg(feature = "serde")]
+    fn to_cbor<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyBytes>> {
+        serialize::to_cbor(py, self)
+    }
+
+    #[cfg(feature = "serde")]
+    #[staticmethod]
+    fn from_cbor(data: &[u8]) -> PyResult<Pluq> {
+        serialize::from_cbor(data)
+    }
+}
+
+impl Pluq {
+    pub fn decompose(a: &Matrix) -> Self {
+        let nrows = a.nrows();
+        let ncols = a.ncols();
+        // Below the diagonal `w` accumulates L, on and above it U.
+        let mut w = a.clone();
+        let mut row_perm: Vec<usize> = (0..nrows).collect();
+        let mut col_perm: Vec<usize> = (0..ncols).collect();
+        let mut r = 0;
+
+        while r < nrows && r < ncols {
+            let pivot = (r..nrows).find_map(|i| bits::first_one_from(&w.rows[i], r).map(|j| (i, j)));
+            let Some((i, j)) = pivot else {
+                break;
+            };
+            w.swap_rows(r, i);
+            row_perm.swap(r, i);
+            w.swap_columns(r, j);
+            col_perm.swap(r, j);
+
+            for i in r + 1..nrows {
+                let (row, pivot_row) = w.rows.pair_mut(i, r);
+                if bits::get_bit(row, r) == 1 {
+                    bits::xor_from(row, pivot_row, r + 1);
+                }
+            }
+            r += 1;
+        }
+
+        let l_rows = w
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let mut l_row = bits::truncate(row, i.min(r));
+                l_row.resize(bits::words_for(r), 0);
+                if i < r {
+                    bits::set_bit(&mut l_row, i, 1);
+                }
+                l_row
+            });
+        let u_rows = w.rows.iter().take(r).enumerate().map(|(i, row)| {
+            let mut u_row = row.to_vec();
+            bits::xor_into(&mut u_row, &bits::truncate(row, i));
+            u_row
+        });
+
+        Pluq {
+            row_perm,
+            col_perm,
+            l: Matrix::from_rows(l_rows, r),
+            u: Matrix::from_rows(u_rows, ncols),
+            rank: r,
+        }
+    }
+
+    /// Solves `A x = b` for packed `b`, setting every non-pivot unknown to zero.
+    pub fn solve(&self, b: &[u64]) -> Option<Vec<u64>> {
+        let r = self.rank;
+        let ncols = self.col_perm.len();
+
+        // L z = P^T b on the first r rows, the remaining rows must agree.
+        let mut z = vec![0u64; bits::words_for(r)];
+        for (i, &row) in self.row_perm.iter().enumerate() {
+            let value = bits::get_bit(b, row) ^ bits::dot(&self.l.rows[i], &z);
+            if i < r {
+                bits::set_bit(&mut z, i, value);
+            } else if value == 1 {
+                return None;
+            }
+        }
+
+        // U y = z with the free unknowns y[r..] set to zero, then x = Q^T y.
+        let mut y = vec![0u64; bits::words_for(ncols)];
+        for i in (0..r).rev() {
+            let value = bits::get_bit(&z, i) ^ bits::dot(&self.u.rows[i], &y);
+            bits::set_bit(&mut y, i, value);
+        }
+        Some(self.unpermute_columns(&y))
+    }
+
+    // x = Q^T y
+    fn unpermute_columns(&self, y: &[u64]) -> Vec<u64> {
+        let mut x = vec![0u64; bits::words_for(self.col_perm.len())];
+        for (j, &col) in self.col_perm.iter().enumerate() {
+            bits::set_bit(&mut x, col, bits::get_bit(y, j));
+        }
+        x
+    }
+
+    /// Basis of the right kernel `{x : A x = 0}`, one packed vector per non-pivot column.
+    pub fn kernel_basis(&self) -> Vec<Vec<u64>> {
+        let r = self.rank;
+        let ncols = self.col_perm.len();
+        (r..ncols)
+            .map(|free| {
+                let mut y = vec![0u64; bits::words_for(ncols)];
+                bits::set_bit(&mut y, free, 1);
+                for i in (0..r).rev() {
+                    let value = bits::dot(&self.u.rows[i], &y);
+                    bits::set_bit(&mut y, i, value);
+                }
+                self.unpermute_columns(&y)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,34 @@
+// Powers of square matrices, for jumping a linear state machine (LFSR, filter
+// generator, ...) far ahead.
+//
+// `pow` squares and multiplies, so its cost grows with the bit length of the exponent.
+// `apply_pow` never forms the power: `x^k` is reduced modulo the minimal polynomial of
+// the vector, which has degree at most `n`, and the remainder is applied to the vector.
+use crate::bits;
+use crate::frobenius;
+use crate::gf2x;
+use crate::matrix::Matrix;
+
+impl Matrix {
+    /// `self^k` for the exponent with little-endian words `k`.
+    pub fn pow(&self, k: &[u64]) -> Matrix {
+        let mut result: Option<Matrix> = None;
+        for i in (0..k.len() * bits::WORD_BITS).rev() {
+            result = result.map(|r| r.mul(&r));
+            if bits::get_bit(k, i) == 1 {
+                result = Some(match result {
+                    Some(r) => r.mul(self),
+                    None => self.clone(),
+                });
+            }
+        }
+        result.unwrap_or_else(|| Matrix::identity(self.nrows()))
+    }
+
+    /// `self^k v` for the packed column vector `v` and the exponent with little-endian
+    /// words `k`.
+    pub fn apply_pow(&self, v: &[u64], k: &[u64]) -> Vec<u64> {
+        let minpoly = frobenius::vector_minpoly(self, v);
+        frobenius::apply(self, &gf2x::pow_x_mod(k, &minpoly), v)
+    }
+}
@@ -0,0 +1,60 @@
+// Seeded random matrices.
+//
+// Generators are ChaCha8 streams seeded from a `u64`, so a seed yields the same matrix
+// on every platform. Full-rank factors are drawn by rejection, which keeps them uniform;
+// a random square matrix over GF(2) is invertible with probability about 0.29.
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use crate::bits;
+use crate::matrix::Matrix;
+
+/// Generator seeded with `seed`, or from the operating system when `seed` is `None`.
+pub fn rng(seed: Option<u64>) -> ChaCha8Rng {
+    match seed {
+        Some(seed) => ChaCha8Rng::seed_from_u64(seed),
+        None => ChaCha8Rng::from_entropy(),
+    }
+}
+
+impl Matrix {
+    /// Uniformly random `nrows x ncols` matrix.
+    pub fn random(nrows: usize, ncols: usize, rng: &mut ChaCha8Rng) -> Matrix {
+        let rows = (0..nrows)
+            .map(|_| {
+                let row: Vec<u64> = (0..bits::words_for(ncols)).map(|_| rng.next_u64()).collect();
+                bits::truncate(&row, ncols)
+            });
+        Matrix::from_rows(rows, ncols)
+    }
+
+    /// Uniformly random `nrows x ncols` matrix of rank `rank`, as the product of random
+    /// full-rank `nrows x rank` and `rank x ncols` factors.
+    ///
+    /// Panics if `rank > min(nrows, ncols)`.
+    pub fn random_of_rank(nrows: usize, ncols: usize, rank: usize, rng: &mut ChaCha8Rng) -> Matrix {
+        assert!(
+            rank <= nrows.min(ncols),
+            "a {}x{} matrix cannot have rank {}",
+            nrows,
+            ncols,
+            rank
+        );
+        let left = Matrix::random_full_rank(nrows, rank, rng);
+        let right = Matrix::random_full_rank(rank, ncols, rng);
+        left.mul(&right)
+    }
+
+    /// Uniformly random invertible `n x n` matrix.
+    pub fn random_invertible(n: usize, rng: &mut ChaCha8Rng) -> Matrix {
+        Matrix::random_full_rank(n, n, rng)
+    }
+
+    fn random_full_rank(nrows: usize, ncols: usize, rng: &mut ChaCha8Rng) -> Matrix {
+        loop {
+            let m = Matrix::random(nrows, ncols, rng);
+            if m.rank() == nrows.min(ncols) {
+                return m;
+            }
+        }
+    }
+}
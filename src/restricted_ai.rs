@@ -1,14 +1,20 @@
 use itertools::Itertools;
 use rayon::prelude::*;
-use crate::matrix::{Matrix, str_ops, verify};
-use std::cmp::Ordering;
+use crate::error::{self, Error};
+use crate::incremental::IncrementalEchelon;
+use crate::bits;
+use crate::matrix::{str_ops, verify, RowBasis};
+#[cfg(feature = "serde")]
+use crate::serialize;
 use pyo3::prelude::*;
 use std::collections::HashSet;
 
 
 #[pyclass]
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RestrictedAI {
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "serialize::binary_values"))]
     truth_table: Vec<u8>
 }
 
@@ -16,11 +22,32 @@ pub struct RestrictedAI {
 impl RestrictedAI {
 
     #[new]
-    pub fn new(truth_table: Vec<u8>) -> Self {
-        RestrictedAI { truth_table }
+    pub fn new(truth_table: Vec<u8>) -> PyResult<Self> {
+        error::check_binary(&truth_table)?;
+        Ok(RestrictedAI { truth_table })
     }
 
-    fn compute_z(&self, subset: Vec<usize>, n: usize) -> (Vec<String>, Vec<String>, Vec<String>) {
+    fn compute_z(&self, subset: Vec<usize>, n: usize) -> PyResult<(Vec<String>, Vec<String>, Vec<String>)> {
+        // The monomials in `n` variables are enumerated, so `2^n` has to be a `usize`.
+        let Some(size) = u32::try_from(n).ok().and_then(|bits| 1usize.checked_shl(bits)) else {
+            return Err(Error::dimension(format!(
+                "{} variables is too many, at most {} are supported",
+                n,
+                usize::BITS - 1
+            ))
+            .into());
+        };
+        if self.truth_table.len() > size {
+            return Err(Error::dimension(format!(
+                "a truth table of length {} does not fit {} variables",
+                self.truth_table.len(),
+                n
+            ))
+            .into());
+        }
+        for &i in &subset {
+            error::check_index(i, self.truth_table.len())?;
+        }
         let mut true_idxs = Vec::new();
         let mut false_idxs = Vec::new();
         let mut s_bin = Vec::new();
@@ -40,16 +67,16 @@ impl RestrictedAI {
             s_bin.push(bin_str);
         }
 
-        (true_idxs, false_idxs, s_bin)
+        Ok((true_idxs, false_idxs, s_bin))
     }
 
     #[staticmethod]
-    pub fn algebraic_immunity(truth_table: Vec<u8>, subset: Vec<usize>, n: usize) -> usize {
-        let restricted_ai = Self::new(truth_table);
-        let (z, z_c, s_bin) = restricted_ai.compute_z(subset, n);
+    pub fn algebraic_immunity(truth_table: Vec<u8>, subset: Vec<usize>, n: usize) -> PyResult<usize> {
+        let restricted_ai = Self::new(truth_table)?;
+        let (z, z_c, s_bin) = restricted_ai.compute_z(subset, n)?;
 
         if z.is_empty() || z_c.is_empty() {
-            return 0;
+            return Ok(0);
         }
 
         let e = Self::generate_combinations(n, n);
@@ -66,14 +93,35 @@ impl RestrictedAI {
             })
             .collect();
 
-        match results.into_iter().flatten().min() {
-            Some(min_val) => min_val,
-            None => 0,
-        }
+        Ok(results.into_iter().flatten().min().unwrap_or_default())
     }
 
 
 
+
+    /// JSON object holding the truth table.
+    #[cfg(feature = "serde")]
+    fn to_json(&self) -> PyResult<String> {
+        serialize::to_json(self)
+    }
+
+    #[cfg(feature = "serde")]
+    #[staticmethod]
+    fn from_json(text: &str) -> PyResult<RestrictedAI> {
+        serialize::from_json(text)
+    }
+
+    /// Compact CBOR encoding of `to_json`.
+    #[cfg(feature = "serde")]
+    fn to_cbor<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyBytes>> {
+        serialize::to_cbor(py, self)
+    }
+
+    #[cfg(feature = "serde")]
+    #[staticmethod]
+    fn from_cbor(data: &[u8]) -> PyResult<RestrictedAI> {
+        serialize::from_cbor(data)
+    }
 }
 
 impl RestrictedAI{
@@ -102,30 +150,26 @@ impl RestrictedAI{
         mut e: Vec<String>,
         s: Vec<String>,
     ) -> Option<usize> {
-        let mut vander_monde = Matrix::new(vec![
-            vec![str_ops(&z[0], &e[0])]
-        ]);
+        let mut vander_monde = IncrementalEchelon::new(1);
+        vander_monde.push_row(&[str_ops(&z[0], &e[0])]);
 
         let mut idx = 0;
         let mut i = 1;
-        let mut operations: Vec<(usize, usize)> = vec![];
 
         let n_iters = z.len();
 
         while i < n_iters {
             let vander_monde_old = vander_monde.clone();
 
-            vander_monde = vander_monde.compute_next(e[..=i].to_vec(), z[..=i].to_vec(), i, operations.clone());
-            let (new_matrix, operations_i) = vander_monde.row_echelon_full_matrix();
-            vander_monde = new_matrix;
-
-            if vander_monde.rank() < i + 1 {
-                let kernel = vander_monde.kernel();
-                let k = &kernel[0];
+            let column: Vec<u8> = z[..i].iter().map(|z_j| str_ops(z_j, &e[i])).collect();
+            vander_monde.push_column(&column);
+            let row: Vec<u8> = e[..=i].iter().map(|e_j| str_ops(&z[i], e_j)).collect();
+            vander_monde.push_row(&row);
 
+            if let Some(k) = vander_monde.kernel_vector() {
                 let (vanish_on_z, vanish_index_opt) = verify(z[i + 1..].to_vec(), k.clone(), e[..=i].to_vec());
                 if vanish_on_z {
-                    let (vanish_on_s, _) = verify(z_c.clone(), k.clone(), e[..=i].to_vec());
+                    let (vanish_on_s, _) = verify(z_c.clone(), k, e[..=i].to_vec());
                     if !vanish_on_s {
                         return Some(e[i].chars().filter(|c| *c == '1').count());
                     } else {
@@ -143,51 +187,40 @@ impl RestrictedAI{
 
             i += 1;
             idx += 1;
-            operations.extend(operations_i);
         }
 
-        let mut vander_monde_s = Matrix::new(Matrix::compute_vandermonde(s[..=idx].to_vec(), e[..=idx].to_vec()));
-        vander_monde_s = vander_monde_s.fill_rows(s[idx + 1..].to_vec(), e[..=idx].to_vec());
-
-        let (vander_monde_s_reduced, mut operations_s) = vander_monde_s.row_echelon_full_matrix();
-        let mut r_s = vander_monde_s_reduced.rank();
+        // Only the rank of the Vandermonde matrix of S is needed, so its columns are
+        // streamed into a basis as rows of the transpose.
+        let s_column = |e_k: &String| {
+            let column: Vec<u8> = s.iter().map(|s_j| str_ops(s_j, e_k)).collect();
+            bits::pack(&column, s.len())
+        };
+        let mut vander_monde_s = RowBasis::new(s.len());
+        for e_k in &e[..=idx] {
+            vander_monde_s.insert(s_column(e_k));
+        }
 
-        if vander_monde.rank() < r_s {
+        if vander_monde.rank() < vander_monde_s.rank() {
             return Some(e[idx].chars().filter(|c| *c == '1').count());
         }
 
         i = idx + 1;
         let s_len = s.len();
-        let mut vander_monde_s = vander_monde_s_reduced;
 
-        while r_s <= (s_len + 1) / 2 {
+        while vander_monde_s.rank() <= s_len.div_ceil(2) {
             if i >= e.len() {
                 break;
             }
 
-            vander_monde = vander_monde.construct_and_add_column(
-                z.clone(),
-                e[i].clone(),
-                operations.clone()
-            );
-
-            vander_monde_s = vander_monde_s.construct_and_add_column(
-                s.clone(),
-                e[i].clone(),
-                operations_s.clone()
-            );
-
-            let (vander_monde_s_new, ops_s) = vander_monde_s.row_echelon_full_matrix();
-            vander_monde_s = vander_monde_s_new;
-
-            r_s = vander_monde_s.rank();
+            let column: Vec<u8> = z[..vander_monde.nrows()].iter().map(|z_j| str_ops(z_j, &e[i])).collect();
+            vander_monde.push_column(&column);
+            vander_monde_s.insert(s_column(&e[i]));
 
-            if vander_monde.rank() < r_s {
+            if vander_monde.rank() < vander_monde_s.rank() {
                 return Some(e[i].chars().filter(|c| *c == '1').count());
             }
 
             i += 1;
-            operations_s.extend(ops_s);
         }
 
 
@@ -0,0 +1,189 @@
+// Row storage of a `Matrix`.
+//
+// Every row takes `stride` words of one contiguous buffer, so a whole matrix can be
+// exported as a strided array without copying. The buffer is shared between clones and
+// copied on the first write (`Arc::make_mut`): an exported view keeps the words it was
+// given, and writing to the matrix afterwards never pulls memory from under it.
+use std::ops::{Index, IndexMut, Range};
+use std::sync::Arc;
+use rayon::prelude::*;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Rows {
+    words: Arc<Vec<u64>>,
+    stride: usize,
+    len: usize,
+}
+
+impl Rows {
+    pub(crate) fn zeros(len: usize, stride: usize) -> Self {
+        Rows { words: Arc::new(vec![0; len * stride]), stride, len }
+    }
+
+    /// Rows laid out in `words`, `stride` words each.
+    pub(crate) fn from_words(words: Vec<u64>, len: usize, stride: usize) -> Self {
+        assert_eq!(words.len(), len * stride);
+        Rows { words: Arc::new(words), stride, len }
+    }
+
+    /// Copies `rows` in, each padded with zero words or cut to `stride` words.
+    pub(crate) fn collect<R: AsRef<[u64]>>(rows: impl IntoIterator<Item = R>, stride: usize) -> Self {
+        let mut out = Rows::zeros(0, stride);
+        for row in rows {
+            out.push(row.as_ref());
+        }
+        out
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Words per row.
+    pub(crate) fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// The buffer, row after row.
+    pub(crate) fn words(&self) -> &Arc<Vec<u64>> {
+        &self.words
+    }
+
+    pub(crate) fn iter(&self) -> Iter<'_> {
+        Iter { rows: self, range: 0..self.len }
+    }
+
+    pub(crate) fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut [u64]> + ExactSizeIterator {
+        let stride = self.stride;
+        let mut rest = Arc::make_mut(&mut self.words).as_mut_slice();
+        (0..self.len).map(move |_| {
+            let (row, tail) = std::mem::take(&mut rest).split_at_mut(stride);
+            rest = tail;
+            row
+        })
+    }
+
+    // Rows without words have nothing to work on, so they are skipped by the parallel
+    // iterators.
+    pub(crate) fn par_iter(&self) -> rayon::slice::Chunks<'_, u64> {
+        self.words.par_chunks(self.stride.max(1))
+    }
+
+    pub(crate) fn par_iter_mut(&mut self) -> rayon::slice::ChunksMut<'_, u64> {
+        let stride = self.stride.max(1);
+        Arc::make_mut(&mut self.words).par_chunks_mut(stride)
+    }
+
+    /// Row `target` for writing, together with row `source`; the two must differ.
+    pub(crate) fn pair_mut(&mut self, target: usize, source: usize) -> (&mut [u64], &[u64]) {
+        assert_ne!(target, source);
+        let stride = self.stride;
+        let words = Arc::make_mut(&mut self.words);
+        if target < source {
+            let (head, tail) = words.split_at_mut(source * stride);
+            (&mut head[target * stride..(target + 1) * stride], &tail[..stride])
+        } else {
+            let (head, tail) = words.split_at_mut(target * stride);
+            (&mut tail[..stride], &head[source * stride..(source + 1) * stride])
+        }
+    }
+
+    pub(crate) fn swap(&mut self, i: usize, j: usize) {
+        if i != j {
+            let (lo, hi, stride) = (i.min(j), i.max(j), self.stride);
+            let (head, tail) = Arc::make_mut(&mut self.words).split_at_mut(hi * stride);
+            head[lo * stride..(lo + 1) * stride].swap_with_slice(&mut tail[..stride]);
+        }
+    }
+
+    /// Appends `row`, padded with zero words or cut to `stride` words.
+    pub(crate) fn push(&mut self, row: &[u64]) {
+        let words = Arc::make_mut(&mut self.words);
+        let n = row.len().min(self.stride);
+        words.extend_from_slice(&row[..n]);
+        words.resize(words.len() + self.stride - n, 0);
+        self.len += 1;
+    }
+
+    /// Appends the rows of `other`, which must have the same stride.
+    pub(crate) fn extend(&mut self, other: &Rows) {
+        assert_eq!(self.stride, other.stride);
+        Arc::make_mut(&mut self.words).extend_from_slice(&other.words);
+        self.len += other.len;
+    }
+
+    /// Keeps the first `len` rows, adding zero rows if there are fewer.
+    pub(crate) fn resize(&mut self, len: usize) {
+        Arc::make_mut(&mut self.words).resize(len * self.stride, 0);
+        self.len = len;
+    }
+
+    /// Copy of the rows in `range`.
+    pub(crate) fn slice(&self, range: Range<usize>) -> Rows {
+        let words = self.words[range.start * self.stride..range.end * self.stride].to_vec();
+        Rows { words: Arc::new(words), stride: self.stride, len: range.len() }
+    }
+
+    /// Lays the rows out with `stride` words each, padding with zero words or cutting.
+    pub(crate) fn set_stride(&mut self, stride: usize) {
+        if stride != self.stride {
+            *self = Rows::collect(self.iter(), stride);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct Iter<'a> {
+    rows: &'a Rows,
+    range: Range<usize>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a [u64];
+
+    fn next(&mut self) -> Option<&'a [u64]> {
+        self.range.next().map(|i| &self.rows[i])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.range.next_back().map(|i| &self.rows[i])
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl<'a> IntoIterator for &'a Rows {
+    type Item = &'a [u64];
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl Index<usize> for Rows {
+    type Output = [u64];
+
+    fn index(&self, i: usize) -> &[u64] {
+        assert!(i < self.len, "row {} out of range for {} rows", i, self.len);
+        &self.words[i * self.stride..(i + 1) * self.stride]
+    }
+}
+
+impl IndexMut<usize> for Rows {
+    fn index_mut(&mut self, i: usize) -> &mut [u64] {
+        assert!(i < self.len, "row {} out of range for {} rows", i, self.len);
+        let stride = self.stride;
+        &mut Arc::make_mut(&mut self.words)[i * stride..(i + 1) * stride]
+    }
+}
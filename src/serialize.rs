@@ -0,0 +1,111 @@
+// JSON and CBOR encodings, behind the `serde` feature.
+//
+// Result types derive `Serialize` and `Deserialize`. A `Matrix` is written in a form that
+// suits the format: JSON gets one string of 0s and 1s per row,
+//
+//   {"ncols": 3, "rows": ["101", "011"]}
+//
+// and CBOR, meant for bulk storage, gets the rows packed as in `Matrix.to_bytes`:
+//
+//   {"nrows": 2, "ncols": 3, "data": h'0506'}
+//
+// Both are validated when read, so a decoded matrix is always well formed.
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use serde::de::{DeserializeOwned, Error as _};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_bytes::ByteBuf;
+use crate::bits;
+use crate::error::{self, Error};
+use crate::matrix::{self, Matrix};
+
+#[derive(Serialize, Deserialize)]
+struct Rows {
+    ncols: usize,
+    rows: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Packed {
+    nrows: usize,
+    ncols: usize,
+    data: ByteBuf,
+}
+
+impl Serialize for Matrix {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            let rows = self
+                .rows
+                .iter()
+                .map(|row| bits::unpack(row, self.ncols).iter().map(|&b| char::from(b'0' + b)).collect())
+                .collect();
+            Rows { ncols: self.ncols, rows }.serialize(serializer)
+        } else {
+            let data = ByteBuf::from(self.packed_bytes());
+            Packed { nrows: self.nrows(), ncols: self.ncols, data }.serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Matrix {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let Rows { ncols, rows } = Rows::deserialize(deserializer)?;
+            error::check_binary_strings(&rows).map_err(D::Error::custom)?;
+            if let Some(row) = rows.iter().find(|row| row.len() != ncols) {
+                let message = format!("{:?} has length {}, expected {}", row, row.len(), ncols);
+                return Err(D::Error::custom(Error::dimension(message)));
+            }
+            let rows = rows
+                .iter()
+                .map(|row| bits::pack(&row.bytes().map(|c| c - b'0').collect::<Vec<_>>(), ncols));
+            Ok(Matrix::from_rows(rows, ncols))
+        } else {
+            let Packed { nrows, ncols, data } = Packed::deserialize(deserializer)?;
+            let row_bytes = ncols.div_ceil(8);
+            if Some(data.len()) != nrows.checked_mul(row_bytes) {
+                return Err(D::Error::custom(Error::dimension(format!(
+                    "expected {} bytes for a {}x{} matrix, got {}",
+                    nrows.saturating_mul(row_bytes),
+                    nrows,
+                    ncols,
+                    data.len()
+                ))));
+            }
+            let rows = data
+                .chunks(row_bytes.max(1))
+                .take(nrows)
+                .map(|row| matrix::bytes_to_row(row, ncols));
+            let mut m = Matrix::from_rows(rows, ncols);
+            m.rows.resize(nrows);
+            Ok(m)
+        }
+    }
+}
+
+/// Deserializes a list of entries, checking that they are 0 or 1.
+pub fn binary_values<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let values = Vec::<u8>::deserialize(deserializer)?;
+    error::check_binary(&values).map_err(D::Error::custom)?;
+    Ok(values)
+}
+
+pub fn to_json<T: Serialize>(value: &T) -> PyResult<String> {
+    serde_json::to_string(value).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+pub fn from_json<T: DeserializeOwned>(text: &str) -> PyResult<T> {
+    serde_json::from_str(text).map_err(|e| PyValueError::new_err(format!("invalid JSON: {}", e)))
+}
+
+pub fn to_cbor<'py, T: Serialize>(py: Python<'py>, value: &T) -> PyResult<Bound<'py, PyBytes>> {
+    let mut data = Vec::new();
+    ciborium::into_writer(value, &mut data).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(PyBytes::new(py, &data))
+}
+
+pub fn from_cbor<T: DeserializeOwned>(data: &[u8]) -> PyResult<T> {
+    ciborium::from_reader(data).map_err(|e| PyValueError::new_err(format!("invalid CBOR: {}", e)))
+}
@@ -0,0 +1,460 @@
+// Sparse GF(2) matrices and structured Gaussian elimination.
+//
+// Rows are stored as sorted lists of the columns holding a one. Elimination picks pivots
+// with the Markowitz rule, minimising `(row weight - 1) * (column count - 1)`, which
+// bounds the fill-in caused by each pivot; singleton columns cost nothing and go first.
+// The search looks at columns by increasing count and rows by increasing weight, and stops
+// once no entry left unseen can beat the best cost, so it only touches the sparsest part.
+// Once the remaining active part gets denser than `DENSE_THRESHOLD` it is packed into a
+// dense `Matrix` and finished there, since dense word operations beat index lists then.
+use pyo3::prelude::*;
+use crate::bits;
+use crate::error::{self, Error};
+use crate::lanczos;
+use crate::matrix::{AffineSolution, Matrix};
+
+/// Fraction of ones in the active submatrix above which elimination switches to dense.
+const DENSE_THRESHOLD: f64 = 0.1;
+
+#[pyclass]
+#[derive(Clone)]
+pub struct SparseMatrix {
+    // Sorted column indices of the ones of each row.
+    pub(crate) rows: Vec<Vec<usize>>,
+    pub(crate) ncols: usize,
+}
+
+#[pymethods]
+impl SparseMatrix {
+    /// Builds a matrix from the column indices of the ones of each row. Repeated
+    /// indices add up modulo 2.
+    #[new]
+    pub fn new(rows: Vec<Vec<usize>>, ncols: usize) -> PyResult<Self> {
+        let rows = rows
+            .into_iter()
+            .map(|mut row| {
+                if let Some(&col) = row.iter().find(|&&col| col >= ncols) {
+                    return Err(Error::Index { index: col as isize, len: ncols }.into());
+                }
+                row.sort_unstable();
+                let mut reduced: Vec<usize> = Vec::with_capacity(row.len());
+                for col in row {
+                    if reduced.last() == Some(&col) {
+                        reduced.pop();
+                    } else {
+                        reduced.push(col);
+                    }
+                }
+                Ok(reduced)
+            })
+            .collect::<PyResult<_>>()?;
+        Ok(SparseMatrix { rows, ncols })
+    }
+
+    #[staticmethod]
+    pub fn from_dense(m: PyRef<'_, Matrix>) -> Self {
+        let rows = m
+            .rows
+            .iter()
+            .map(|row| {
+                let mut cols = Vec::new();
+                let mut j = bits::first_one(row);
+                while let Some(col) = j {
+                    cols.push(col);
+                    j = bits::first_one_from(row, col + 1);
+                }
+                cols
+            })
+            .collect();
+        SparseMatrix { rows, ncols: m.ncols() }
+    }
+
+    pub fn to_dense(&self) -> Matrix {
+        let mut m = Matrix::zeros(self.nrows(), self.ncols);
+        for (dense_row, row) in m.rows.iter_mut().zip(self.rows.iter()) {
+            for &col in row {
+                bits::set_bit(dense_row, col, 1);
+            }
+        }
+        m
+    }
+
+    pub fn nrows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    /// Number of ones.
+    pub fn nnz(&self) -> usize {
+        self.rows.iter().map(Vec::len).sum()
+    }
+
+    pub fn density(&self) -> f64 {
+        let size = self.nrows() * self.ncols;
+        if size == 0 {
+            0.0
+        } else {
+            self.nnz() as f64 / size as f64
+        }
+    }
+
+    /// Column indices of the ones of row `i`.
+    pub fn row(&self, i: usize) -> PyResult<Vec<usize>> {
+        error::check_index(i, self.nrows())?;
+        Ok(self.rows[i].clone())
+    }
+
+    pub fn to_rows(&self) -> Vec<Vec<usize>> {
+        self.rows.clone()
+    }
+
+    /// Matrix-vector product `self * v`.
+    fn mul_vec(&self, v: Vec<u8>) -> PyResult<Vec<u8>> {
+        if v.len() != self.ncols {
+            return Err(Error::dimension(format!(
+                "vector of length {} does not match {} columns",
+                v.len(),
+                self.ncols
+            ))
+            .into());
+        }
+        error::check_binary(&v)?;
+        Ok(self
+            .rows
+            .iter()
+            .map(|row| row.iter().fold(0, |acc, &col| acc ^ (v[col] & 1)))
+            .collect())
+    }
+
+    pub fn rank(&self) -> usize {
+        self.eliminate(None).pivots.len()
+    }
+
+    /// Basis of the right kernel `{x : self * x = 0}`, one vector per column left without
+    /// a pivot. The pivots follow the Markowitz order, so the basis can differ from
+    /// `Matrix.kernel` while spanning the same space.
+    pub fn kernel(&self) -> Vec<Vec<u8>> {
+        self.eliminate(None).kernel_basis()
+    }
+
+    /// Solves `self * x = b`, returning `None` if the system is inconsistent, otherwise
+    /// a particular solution together with a basis of the solutions of `self * x = 0`.
+    #[pyo3(name = "solve")]
+    fn py_solve(&self, b: Vec<u8>) -> PyResult<Option<AffineSolution>> {
+        if b.len() != self.nrows() {
+            return Err(Error::dimension(format!(
+                "right-hand side of length {} does not match {} rows",
+                b.len(),
+                self.nrows()
+            ))
+            .into());
+        }
+        error::check_binary(&b)?;
+        let elimination = self.eliminate(Some(&b));
+        if !elimination.consistent {
+            return Ok(None);
+        }
+        let mut x = vec![0u8; self.ncols];
+        elimination.back_substitute(&mut x, true);
+        Ok(Some((x, elimination.kernel_basis())))
+    }
+
+    /// Kernel vectors found by block Lanczos, for systems too large to eliminate.
+    ///
+    /// Only products with `self` and its transpose are used. Up to about 64 independent
+    /// vectors are returned per call; an empty list means no dependency was found. Pass
+    /// `seed` for reproducible results.
+    #[pyo3(signature = (seed = None))]
+    fn block_lanczos_kernel(&self, py: Python<'_>, seed: Option<u64>) -> Vec<Vec<u8>> {
+        let vectors = py.allow_threads(|| lanczos::kernel(self, seed));
+        vectors.iter().map(|v| bits::unpack(v, self.ncols)).collect()
+    }
+
+    /// A solution of `self * x = b` found by block Lanczos, or `None` if none was found.
+    #[pyo3(signature = (b, seed = None))]
+    fn block_lanczos_solve(&self, py: Python<'_>, b: Vec<u8>, seed: Option<u64>) -> PyResult<Option<Vec<u8>>> {
+        if b.len() != self.nrows() {
+            return Err(Error::dimension(format!(
+                "right-hand side of length {} does not match {} rows",
+                b.len(),
+                self.nrows()
+            ))
+            .into());
+        }
+        error::check_binary(&b)?;
+        let x = py.allow_threads(|| lanczos::solve(self, &b, seed));
+        Ok(x.map(|x| bits::unpack(&x, self.ncols)))
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "SparseMatrix(nrows={}, ncols={}, nnz={})",
+            self.nrows(),
+            self.ncols,
+            self.nnz()
+        )
+    }
+}
+
+/// Triangular system left by the elimination: pivot row `k` holds its pivot column and
+/// otherwise only columns that are free or pivots of later rows.
+struct Elimination {
+    // (pivot column, sorted row, right-hand side bit), in elimination order.
+    pivots: Vec<(usize, Vec<usize>, u8)>,
+    is_pivot: Vec<bool>,
+    consistent: bool,
+}
+
+impl Elimination {
+    /// Fills the pivot unknowns of `x` from its free unknowns, in reverse pivot order.
+    fn back_substitute(&self, x: &mut [u8], with_rhs: bool) {
+        for (col, row, rhs) in self.pivots.iter().rev() {
+            let mut value = if with_rhs { *rhs } else { 0 };
+            for &j in row.iter().filter(|&&j| j != *col) {
+                value ^= x[j];
+            }
+            x[*col] = value;
+        }
+    }
+
+    fn kernel_basis(&self) -> Vec<Vec<u8>> {
+        (0..self.is_pivot.len())
+            .filter(|&j| !self.is_pivot[j])
+            .map(|free_col| {
+                let mut x = vec![0u8; self.is_pivot.len()];
+                x[free_col] = 1;
+                self.back_substitute(&mut x, false);
+                x
+            })
+            .collect()
+    }
+}
+
+impl SparseMatrix {
+    fn eliminate(&self, rhs: Option<&[u8]>) -> Elimination {
+        let mut rows = self.rows.clone();
+        let mut rhs: Vec<u8> = rhs.map_or_else(|| vec![0; self.nrows()], |b| b.iter().map(|v| v & 1).collect());
+        let mut elimination = Elimination {
+            pivots: Vec::new(),
+            is_pivot: vec![false; self.ncols],
+            consistent: true,
+        };
+        let mut active = vec![false; self.nrows()];
+        for (r, row) in rows.iter().enumerate() {
+            if row.is_empty() {
+                elimination.consistent &= rhs[r] == 0;
+            } else {
+                active[r] = true;
+            }
+        }
+        let mut active_rows = active.iter().filter(|&&a| a).count();
+        let mut active_cols = self.ncols;
+        let mut nnz = self.nnz();
+        let mut search = Markowitz::new(&rows, self.ncols);
+
+        while active_rows > 0 {
+            if nnz as f64 > DENSE_THRESHOLD * (active_rows * active_cols) as f64 {
+                let remaining: Vec<usize> = (0..self.nrows()).filter(|&r| active[r]).collect();
+                self.finish_dense(&rows, &rhs, &remaining, &mut elimination);
+                break;
+            }
+
+            let (pivot, pivot_col) = search.pivot(&rows, &active);
+            active[pivot] = false;
+            active_rows -= 1;
+            let pivot_row = std::mem::take(&mut rows[pivot]);
+            nnz -= pivot_row.len();
+            for &col in &pivot_row {
+                search.remove(col);
+            }
+            for r in search.rows_with(pivot_col, &rows, &active) {
+                nnz -= rows[r].len();
+                rows[r] = xor_sorted(&rows[r], &pivot_row, r, &mut search);
+                nnz += rows[r].len();
+                rhs[r] ^= rhs[pivot];
+                if rows[r].is_empty() {
+                    active[r] = false;
+                    active_rows -= 1;
+                    elimination.consistent &= rhs[r] == 0;
+                } else {
+                    search.reweigh(r, rows[r].len());
+                }
+            }
+            elimination.is_pivot[pivot_col] = true;
+            elimination.pivots.push((pivot_col, pivot_row, rhs[pivot]));
+            active_cols -= 1;
+        }
+        elimination
+    }
+
+    // Reduces the active rows as a dense matrix over the non-pivot columns, with the
+    // right-hand side as an extra last column, and appends the resulting pivot rows.
+    fn finish_dense(&self, rows: &[Vec<usize>], rhs: &[u8], active: &[usize], elimination: &mut Elimination) {
+        let cols: Vec<usize> = (0..self.ncols).filter(|&j| !elimination.is_pivot[j]).collect();
+        let mut index = vec![0usize; self.ncols];
+        for (k, &col) in cols.iter().enumerate() {
+            index[col] = k;
+        }
+        let width = cols.len();
+
+        let mut dense = Matrix::zeros(active.len(), width + 1);
+        for (dense_row, &r) in dense.rows.iter_mut().zip(active.iter()) {
+            for &col in &rows[r] {
+                bits::set_bit(dense_row, index[col], 1);
+            }
+            bits::set_bit(dense_row, width, rhs[r]);
+        }
+
+        for row in &dense.reduced().rows {
+            match bits::first_one(row) {
+                None => {}
+                Some(p) if p == width => elimination.consistent = false,
+                Some(p) => {
+                    let mut sparse_row = Vec::new();
+                    let mut j = Some(p);
+                    while let Some(k) = j.filter(|&k| k < width) {
+                        sparse_row.push(cols[k]);
+                        j = bits::first_one_from(row, k + 1);
+                    }
+                    elimination.is_pivot[cols[p]] = true;
+                    elimination.pivots.push((cols[p], sparse_row, bits::get_bit(row, width)));
+                }
+            }
+        }
+    }
+}
+
+/// Pivot search state. Column counts are exact; the indexes from columns to the rows
+/// holding them, from weights to rows and from counts to columns are only ever appended
+/// to, and stale or repeated entries are dropped when a lookup meets them.
+struct Markowitz {
+    col_count: Vec<usize>,
+    col_rows: Vec<Vec<usize>>,
+    rows_by_weight: Vec<Vec<usize>>,
+    cols_by_count: Vec<Vec<usize>>,
+    // Last lookup that met each row and column, to drop repeated entries.
+    row_seen: Vec<usize>,
+    col_seen: Vec<usize>,
+    lookups: usize,
+}
+
+impl Markowitz {
+    fn new(rows: &[Vec<usize>], ncols: usize) -> Self {
+        let mut search = Markowitz {
+            col_count: vec![0; ncols],
+            col_rows: vec![Vec::new(); ncols],
+            rows_by_weight: vec![Vec::new(); ncols + 1],
+            cols_by_count: vec![Vec::new(); rows.len() + 1],
+            row_seen: vec![0; rows.len()],
+            col_seen: vec![0; ncols],
+            lookups: 0,
+        };
+        for (r, row) in rows.iter().enumerate() {
+            for &col in row {
+                search.col_count[col] += 1;
+                search.col_rows[col].push(r);
+            }
+            search.rows_by_weight[row.len()].push(r);
+        }
+        for col in 0..ncols {
+            search.cols_by_count[search.col_count[col]].push(col);
+        }
+        search
+    }
+
+    fn add(&mut self, col: usize, row: usize) {
+        self.col_count[col] += 1;
+        self.col_rows[col].push(row);
+        self.cols_by_count[self.col_count[col]].push(col);
+    }
+
+    fn remove(&mut self, col: usize) {
+        self.col_count[col] -= 1;
+        self.cols_by_count[self.col_count[col]].push(col);
+    }
+
+    fn reweigh(&mut self, row: usize, weight: usize) {
+        self.rows_by_weight[weight].push(row);
+    }
+
+    /// Active rows with a one in column `col`.
+    fn rows_with(&mut self, col: usize, rows: &[Vec<usize>], active: &[bool]) -> Vec<usize> {
+        self.lookups += 1;
+        let (lookup, seen) = (self.lookups, &mut self.row_seen);
+        self.col_rows[col].retain(|&r| {
+            active[r] && rows[r].binary_search(&col).is_ok() && std::mem::replace(&mut seen[r], lookup) != lookup
+        });
+        self.col_rows[col].clone()
+    }
+
+    /// Active entry `(row, col)` of least Markowitz cost `(weight - 1) * (count - 1)`.
+    ///
+    /// After the columns of count at most `k` and the rows of weight at most `k` have been
+    /// met, every entry left has a cost of at least `k * k`.
+    fn pivot(&mut self, rows: &[Vec<usize>], active: &[bool]) -> (usize, usize) {
+        fn consider(best: &mut Option<(usize, usize, usize)>, cost: usize, r: usize, col: usize) {
+            if best.is_none_or(|(best_cost, _, _)| cost < best_cost) {
+                *best = Some((cost, r, col));
+            }
+        }
+        let mut best = None;
+        for k in 1..self.rows_by_weight.len().max(self.cols_by_count.len()) {
+            if k < self.cols_by_count.len() {
+                self.lookups += 1;
+                let (lookup, seen, count) = (self.lookups, &mut self.col_seen, &self.col_count);
+                self.cols_by_count[k].retain(|&c| count[c] == k && std::mem::replace(&mut seen[c], lookup) != lookup);
+                for c in self.cols_by_count[k].clone() {
+                    for r in self.rows_with(c, rows, active) {
+                        consider(&mut best, (rows[r].len() - 1) * (k - 1), r, c);
+                    }
+                }
+            }
+            if k < self.rows_by_weight.len() {
+                self.lookups += 1;
+                let (lookup, seen) = (self.lookups, &mut self.row_seen);
+                self.rows_by_weight[k]
+                    .retain(|&r| active[r] && rows[r].len() == k && std::mem::replace(&mut seen[r], lookup) != lookup);
+                for &r in &self.rows_by_weight[k] {
+                    for &c in &rows[r] {
+                        consider(&mut best, (k - 1) * (self.col_count[c] - 1), r, c);
+                    }
+                }
+            }
+            if best.is_some_and(|(cost, _, _)| cost <= k * k) {
+                break;
+            }
+        }
+        let (_, r, col) = best.expect("active rows are non-empty");
+        (r, col)
+    }
+}
+
+// Symmetric difference of two sorted index lists, where `a` is row `row`, keeping the
+// search state up to date.
+fn xor_sorted(a: &[usize], b: &[usize], row: usize, search: &mut Markowitz) -> Vec<usize> {
+    let mut out = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] < b[j] {
+            out.push(a[i]);
+            i += 1;
+        } else if b[j] < a[i] {
+            out.push(b[j]);
+            search.add(b[j], row);
+            j += 1;
+        } else {
+            search.remove(a[i]);
+            i += 1;
+            j += 1;
+        }
+    }
+    out.extend_from_slice(&a[i..]);
+    for &col in &b[j..] {
+        out.push(col);
+        search.add(col, row);
+    }
+    out
+}
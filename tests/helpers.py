@@ -0,0 +1,27 @@
+"""Helpers shared by the test modules."""
+from algebraic_immunity_utils import Matrix as GF2Matrix
+
+
+def rows_of(m):
+    return [list(r) for r in m.to_list()]
+
+
+def random_rows(rng, rows, cols, density=0.5):
+    return [[1 if rng.random() < density else 0 for _ in range(cols)] for _ in range(rows)]
+
+
+def span(basis, n):
+    vectors = {tuple([0] * n)}
+    for b in basis:
+        vectors |= {tuple(x ^ y for x, y in zip(v, b)) for v in vectors}
+    return vectors
+
+
+def companion(coefficients):
+    d = len(coefficients) - 1
+    rows = [[0] * d for _ in range(d)]
+    for i in range(d):
+        if i + 1 < d:
+            rows[i + 1][i] = 1
+        rows[i][d - 1] = coefficients[i]
+    return GF2Matrix(rows)
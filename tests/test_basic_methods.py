@@ -2,6 +2,7 @@ import unittest
 from typing import List, Tuple, Union
 
 from algebraic_immunity_utils import Matrix as GF2Matrix
+from algebraic_immunity_utils import OpLog
 from algebraic_immunity_utils import verify
 
 
@@ -18,12 +19,26 @@ class TestBasicMethods(unittest.TestCase):
         m_res = GF2Matrix([[1, 1], [1, 0], [1, 0]])
         self.assertEqual(m, m_res)
 
+    def test_rows_wider_than_a_word(self):
+        row = [1 if i % 3 == 0 else 0 for i in range(64)]
+        m = GF2Matrix([row, row[::-1]])
+        m.append_column([1, 0])
+        self.assertEqual(m.ncols(), 65)
+        self.assertEqual(m.get(0, 64), 1)
+        self.assertEqual(m.get(1, 64), 0)
+        self.assertEqual(list(m.to_list()[0]), row + [1])
+        self.assertEqual(m.row_weight(0), sum(row) + 1)
+        self.assertEqual(m.weight(), 2 * sum(row) + 1)
+
+        m.add_rows(1, 0)
+        self.assertEqual(list(m.to_list()[1]), [a ^ b for a, b in zip(row[::-1] + [0], row + [1])])
+
     def test_compute_next(self):
         m = GF2Matrix([[1, 1], [1, 0]])
         support_slice = ['00', '01', '10']
         monom_slice = ['00', '01', '10']
         idx = 2
-        operations = []
+        operations = OpLog()
         n = m.compute_next(monom_slice, support_slice, idx, operations)
         self.assertEqual(n.to_list(), [[1, 1, 0], [1, 0, 0], [1, 0, 1]])
 
@@ -31,7 +46,7 @@ class TestBasicMethods(unittest.TestCase):
         support_slice = ['000', '010', '100']
         monom_slice = ['000', '001', '010']
         idx = 2
-        operations = [(0, 1)]
+        operations = OpLog.from_pairs([(0, 1)])
         n_m = m.compute_next(monom_slice, support_slice, idx, operations)
         self.assertEqual(n_m.to_list(), [[1, 1, 1], [0, 1, 1], [1, 0, 0]])
         self.assertEqual(m.to_list(), [[1, 1], [0, 1]])
@@ -41,7 +56,7 @@ class TestBasicMethods(unittest.TestCase):
         support_slice = ['00', '01', '10']
         monom_slice = ['00', '01', '10']
         idx = 2
-        operations = []
+        operations = OpLog()
         n = m.compute_next(monom_slice, support_slice, idx, operations)
         self.assertEqual(n.to_list(), [[1, 1, 0], [1, 0, 0], [1, 0, 1]])
 
@@ -0,0 +1,54 @@
+import unittest
+
+from algebraic_immunity_utils import Matrix as GF2Matrix
+
+from helpers import rows_of
+
+
+class TestConstructors(unittest.TestCase):
+
+    def test_identity_and_zeros(self):
+        self.assertEqual(rows_of(GF2Matrix.identity(3)), [[1, 0, 0], [0, 1, 0], [0, 0, 1]])
+        z = GF2Matrix.zeros(2, 70)
+        self.assertEqual((z.nrows(), z.ncols(), z.weight()), (2, 70, 0))
+
+    def test_from_int_rows(self):
+        m = GF2Matrix.from_int_rows([0b101, 0b010, 1 << 69], 70)
+        self.assertEqual(list(m.to_list()[0][:3]), [1, 0, 1])
+        self.assertEqual(list(m.to_list()[1][:3]), [0, 1, 0])
+        self.assertEqual(m.get(2, 69), 1)
+        self.assertEqual(m.weight(), 4)
+        with self.assertRaises(ValueError):
+            GF2Matrix.from_int_rows([0b1000], 3)
+        with self.assertRaises(ValueError):
+            GF2Matrix.from_int_rows([-1], 3)
+
+    def test_bytes_round_trip(self):
+        m = GF2Matrix.random(5, 13, seed=1)
+        data = m.to_bytes()
+        self.assertEqual(len(data), 5 * 2)
+        self.assertEqual(rows_of(GF2Matrix.from_bytes(data, 5, 13)), rows_of(m))
+        self.assertEqual(rows_of(GF2Matrix.from_bytes(b'\x05\xff', 2, 3)), [[1, 0, 1], [1, 1, 1]])
+        with self.assertRaises(ValueError):
+            GF2Matrix.from_bytes(b'\x00', 2, 3)
+
+    def test_random_is_seeded(self):
+        a = GF2Matrix.random(20, 100, seed=7)
+        self.assertEqual(rows_of(a), rows_of(GF2Matrix.random(20, 100, seed=7)))
+        self.assertNotEqual(rows_of(a), rows_of(GF2Matrix.random(20, 100, seed=8)))
+        self.assertEqual((a.nrows(), a.ncols()), (20, 100))
+
+    def test_random_of_rank(self):
+        for rows, cols, rank in [(10, 20, 0), (10, 20, 7), (30, 30, 30), (200, 150, 100)]:
+            m = GF2Matrix.random_of_rank(rows, cols, rank, seed=rank)
+            self.assertEqual((m.nrows(), m.ncols(), m.rank()), (rows, cols, rank))
+        with self.assertRaises(ValueError):
+            GF2Matrix.random_of_rank(3, 4, 4)
+
+    def test_random_invertible(self):
+        m = GF2Matrix.random_invertible(40, seed=3)
+        self.assertEqual(rows_of(m @ m.inverse()), rows_of(GF2Matrix.identity(40)))
+
+
+if __name__ == '__main__':
+    unittest.main()
@@ -1,3 +1,4 @@
+import random
 import unittest
 
 from algebraic_immunity_utils import Matrix as GF2Matrix
@@ -9,7 +10,7 @@ class TestEchelonForm(unittest.TestCase):
         m = GF2Matrix([[1, 1], [1, 0]])
         m, ops = m.reduced_echelon_form_last_row()
         self.assertEqual(m.to_list(), [[1, 0], [0, 1]])
-        self.assertEqual(ops, [(1, 0), (0, 1)])
+        self.assertEqual(ops.to_pairs(), [(1, 0), (0, 1)])
 
     def test_2(self):
         m = [
@@ -50,3 +51,63 @@ class TestEchelonForm(unittest.TestCase):
             [[1, 0, 0, 0], [0, 1, 0, 0], [0, 0, 0, 1], [0, 0, 0, 0]]
         )
         self.assertEqual(m.to_list(), m2.to_list())
+
+    def test_m4ri_matches_row_echelon_full_matrix(self):
+        rng = random.Random(2024)
+        for rows, cols in [(5, 7), (40, 90), (130, 140), (200, 70)]:
+            m_l = [[rng.randint(0, 1) for _ in range(cols)] for _ in range(rows)]
+            m_l[rows // 2] = list(m_l[0])
+            m = GF2Matrix(m_l)
+            r, ops = m.echelon_form_m4ri()
+            r_ref, _ = GF2Matrix(m_l).row_echelon_full_matrix()
+            self.assertEqual(r.to_list(), r_ref.to_list())
+            self.assertEqual(replay(ops.to_pairs(), m_l), [list(row) for row in r.to_list()])
+            self.assertEqual(m.rank(), sum(1 for row in r.to_list() if any(row)))
+
+    def test_rank_of_unreduced_matrix(self):
+        m = GF2Matrix([[1, 1, 0], [1, 1, 0], [0, 1, 1], [1, 0, 1]])
+        self.assertEqual(m.rank(), 2)
+
+    def test_transform_reproduces_reduced_matrix(self):
+        rng = random.Random(31)
+        for rows, cols in [(4, 4), (9, 6), (140, 150)]:
+            m_l = [[rng.randint(0, 1) for _ in range(cols)] for _ in range(rows)]
+            m = GF2Matrix(m_l)
+            for method in ['echelon_form', 'row_echelon_full_matrix', 'echelon_form_m4ri']:
+                r, ops, u = getattr(m, method)(with_transform=True)
+                self.assertEqual((u @ m).to_list(), r.to_list())
+                self.assertEqual(u.det(), 1)
+                self.assertEqual(getattr(m, method)()[0].to_list(), r.to_list())
+
+    def test_last_row_with_new_pivot(self):
+        m = GF2Matrix([[1, 0, 1], [0, 0, 1]])
+        r, ops = m.reduced_echelon_form_last_row()
+        self.assertEqual([list(row) for row in r.to_list()], [[1, 0, 0], [0, 0, 1]])
+        self.assertEqual(ops.to_pairs(), [(0, 1)])
+        r, ops, u = m.reduced_echelon_form_last_row(with_transform=True)
+        self.assertEqual((u @ m).to_list(), r.to_list())
+
+    def test_last_row_matches_echelon_form(self):
+        m = GF2Matrix([[0, 1, 0, 1], [0, 0, 1, 0], [1, 1, 0, 0]])
+        self.assertEqual(m.reduced_echelon_form_last_row()[0], m.echelon_form()[0])
+        rng = random.Random(6)
+        for seed in range(500):
+            nrows, ncols = rng.randint(1, 8), rng.randint(1, 8)
+            reduced = GF2Matrix.random(nrows, ncols, seed=seed).echelon_form()[0]
+            m = GF2Matrix([list(row) for row in reduced.to_list()] + [[rng.randint(0, 1) for _ in range(ncols)]])
+            r, ops, u = m.reduced_echelon_form_last_row(with_transform=True)
+            self.assertEqual(r, m.echelon_form()[0])
+            self.assertEqual(u @ m, r)
+
+    def test_transform_last_row(self):
+        m = GF2Matrix([[1, 1, 0], [0, 1, 1], [1, 0, 0]])
+        r, ops, u = m.reduced_echelon_form_last_row(with_transform=True)
+        self.assertEqual((u @ m).to_list(), r.to_list())
+        self.assertEqual(replay(ops.to_pairs(), m.to_list()), [list(row) for row in r.to_list()])
+
+
+def replay(ops, rows):
+    rows = [list(row) for row in rows]
+    for target, source in ops:
+        rows[target] = [a ^ b for a, b in zip(rows[target], rows[source])]
+    return rows
@@ -0,0 +1,129 @@
+import os
+import tempfile
+import unittest
+
+from algebraic_immunity_utils import DimensionError
+from algebraic_immunity_utils import Matrix as GF2Matrix
+
+
+class TestFormats(unittest.TestCase):
+
+    def setUp(self):
+        self.dir = tempfile.TemporaryDirectory()
+        self.m = GF2Matrix([[1, 0, 1], [0, 1, 1]])
+
+    def tearDown(self):
+        self.dir.cleanup()
+
+    def path(self, name):
+        return os.path.join(self.dir.name, name)
+
+    def write(self, name, text):
+        path = self.path(name)
+        with open(path, "w") as f:
+            f.write(text)
+        return path
+
+    def read(self, path):
+        with open(path, "rb") as f:
+            return f.read()
+
+    def test_round_trips(self):
+        matrices = [self.m, GF2Matrix.random(70, 130, seed=5), GF2Matrix.zeros(0, 4), GF2Matrix.zeros(3, 0)]
+        for name in ["a.mtx", "a.txt", "a.sage", "a.m", "a.mag"]:
+            for m in matrices:
+                if name.endswith((".txt", ".sage")) and m.nrows() == 0:
+                    continue
+                path = self.path(name)
+                m.save(path)
+                self.assertEqual(GF2Matrix.load(path), m, name)
+        for m in matrices:
+            path = self.path("a.out")
+            m.save(path, format="matrixmarket-array")
+            self.assertEqual(GF2Matrix.load(path, format="matrixmarket"), m)
+
+    def test_written_text(self):
+        self.m.save(self.path("a.mtx"))
+        self.assertEqual(
+            self.read(self.path("a.mtx")).decode(),
+            "%%MatrixMarket matrix coordinate integer general\n2 3 4\n1 1 1\n1 3 1\n2 2 1\n2 3 1\n",
+        )
+        self.m.save(self.path("a.txt"))
+        self.assertEqual(self.read(self.path("a.txt")).decode(), "[1 0 1]\n[0 1 1]\n")
+        self.m.save(self.path("a"), format="magma")
+        self.assertEqual(
+            self.read(self.path("a")).decode(),
+            "Matrix(GF(2), 2, 3, [\n    1, 0, 1,\n    0, 1, 1\n])\n",
+        )
+
+    def test_matrix_market_variants(self):
+        path = self.write("s.mtx", "%%MatrixMarket matrix coordinate pattern symmetric\n% comment\n3 3 2\n2 1\n3 3\n")
+        self.assertEqual(GF2Matrix.load(path), GF2Matrix([[0, 1, 0], [1, 0, 0], [0, 0, 1]]))
+        path = self.write("i.mtx", "%%MatrixMarket matrix coordinate integer general\n2 2 3\n1 1 3\n2 2 2\n1 1 1\n")
+        self.assertEqual(GF2Matrix.load(path), GF2Matrix.zeros(2, 2))
+        path = self.write("a.mtx", "%%MatrixMarket matrix array integer symmetric\n2 2\n1\n-1\n0\n")
+        self.assertEqual(GF2Matrix.load(path), GF2Matrix([[1, 1], [1, 0]]))
+
+    def test_lenient_text(self):
+        path = self.write("a.txt", "\n[1 0|1]\n[. 1 1]\n")
+        self.assertEqual(GF2Matrix.load(path), self.m)
+        path = self.write("b.txt", "101\n011\n")
+        self.assertEqual(GF2Matrix.load(path), self.m)
+        path = self.write("a.m", "M := Matrix(GF(2), [[1, 0, 3], [0, 1, -1]]);")
+        self.assertEqual(GF2Matrix.load(path), self.m)
+        path = self.write("b.m", "Matrix(GF(2), 2, [1, 0, 1, 0, 1, 1])")
+        self.assertEqual(GF2Matrix.load(path), self.m)
+
+    def test_malformed(self):
+        cases = [
+            ("a.mtx", "2 2 0\n"),
+            ("b.mtx", "%%MatrixMarket matrix coordinate real general\n1 1 1\n1 1 1.0\n"),
+            ("c.mtx", "%%MatrixMarket matrix coordinate integer general\n2 2 1\n3 1 1\n"),
+            ("d.mtx", "%%MatrixMarket matrix coordinate integer general\n2 2 2\n1 1 1\n"),
+            ("a.txt", "[1 0 2]\n"),
+            ("a.m", "Matrix(GF(3), 1, 1, [1])"),
+            ("b.m", "Matrix(GF(2), 1, 2, [[1, 0]"),
+        ]
+        for name, text in cases:
+            with self.assertRaises(ValueError, msg=name):
+                GF2Matrix.load(self.write(name, text))
+        with self.assertRaises(DimensionError):
+            GF2Matrix.load(self.write("e.txt", "[1 0]\n[1]\n"))
+        with self.assertRaises(DimensionError):
+            GF2Matrix.load(self.write("c.m", "Matrix(GF(2), 2, 2, [1, 0, 1])"))
+        huge = 10 ** 18
+        with self.assertRaises(DimensionError):
+            GF2Matrix.load(self.write("f.mtx", "%%%%MatrixMarket matrix coordinate integer general\n%d 2 0\n" % huge))
+        with self.assertRaises(DimensionError):
+            GF2Matrix.load(self.write("g.mtx", "%%%%MatrixMarket matrix array integer general\n%d 0\n" % huge))
+        with self.assertRaises(DimensionError):
+            GF2Matrix.load(self.write("d.m", "Matrix(GF(2), %d, 0, [])" % huge))
+        with self.assertRaises(ValueError):
+            self.m.save(self.path("a.unknown"))
+        with self.assertRaises(ValueError):
+            self.m.save(self.path("a.pbm"), format="csv")
+        with self.assertRaises(ValueError):
+            self.m.save(self.path("a.mtx"), block=2)
+        with self.assertRaises(ValueError):
+            GF2Matrix.load(self.path("a.pbm"))
+        with self.assertRaises(FileNotFoundError):
+            GF2Matrix.load(self.path("missing.mtx"))
+
+    def test_pbm(self):
+        m = GF2Matrix.zeros(2, 10)
+        m[0, 0] = 1
+        m[1, 9] = 1
+        m.save(self.path("a.pbm"))
+        self.assertEqual(self.read(self.path("a.pbm")), b"P4\n10 2\n" + bytes([0x80, 0x00, 0x00, 0x40]))
+
+    def test_pgm_blocks(self):
+        m = GF2Matrix([[1, 1, 0], [1, 0, 0], [0, 0, 1]])
+        m.save(self.path("a.pgm"))
+        self.assertEqual(self.read(self.path("a.pgm")), b"P5\n3 3\n255\n" + bytes([0, 0, 255, 0, 255, 255, 255, 255, 0]))
+        m.save(self.path("b.pgm"), block=2)
+        # Blocks of 3, 1, 0 and 1 ones out of 4, 2, 2 and 1 entries.
+        self.assertEqual(self.read(self.path("b.pgm")), b"P5\n2 2\n255\n" + bytes([64, 255, 255, 0]))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,71 @@
+import random
+import unittest
+
+from algebraic_immunity_utils import IncrementalEchelon
+from algebraic_immunity_utils import Matrix as GF2Matrix
+
+from helpers import rows_of
+
+
+class TestIncrementalEchelon(unittest.TestCase):
+
+    def check(self, inc, m_l):
+        m = GF2Matrix(m_l)
+        self.assertEqual(inc.rank(), m.rank())
+        kernel = m.kernel()
+        if kernel:
+            self.assertEqual(list(inc.kernel_vector()), list(kernel[0]))
+        else:
+            self.assertIsNone(inc.kernel_vector())
+        self.assertEqual(rows_of(inc.transform() @ m), rows_of(inc.reduced()))
+        reduced, _ = m.row_echelon_full_matrix()
+        nonzero = [r for r in rows_of(inc.reduced()) if any(r)]
+        nonzero.sort(key=lambda r: r.index(1))
+        self.assertEqual(nonzero, rows_of(reduced)[:len(nonzero)])
+        self.assertEqual(inc.pivot_columns(), [r.index(1) for r in nonzero])
+
+    def test_push_rows(self):
+        rng = random.Random(3)
+        for cols in [1, 5, 70]:
+            inc = IncrementalEchelon(cols)
+            m_l = []
+            for _ in range(8):
+                row = [rng.randint(0, 1) for _ in range(cols)]
+                m_l.append(row)
+                inc.push_row(row)
+                self.check(inc, m_l)
+
+    def test_push_rows_and_columns(self):
+        rng = random.Random(4)
+        inc = IncrementalEchelon(1)
+        m_l = [[1]]
+        inc.push_row([1])
+        for step in range(20):
+            if step % 2:
+                column = [rng.randint(0, 1) for _ in m_l]
+                for row, bit in zip(m_l, column):
+                    row.append(bit)
+                inc.push_column(column)
+            else:
+                row = [rng.randint(0, 1) for _ in m_l[0]]
+                m_l.append(row)
+                inc.push_row(row)
+            self.check(inc, m_l)
+
+    def test_from_matrix(self):
+        m_l = [[1, 1, 0], [1, 1, 0], [0, 1, 1]]
+        inc = IncrementalEchelon.from_matrix(GF2Matrix(m_l))
+        self.assertEqual(inc.rank(), 2)
+        self.assertEqual(list(inc.kernel_vector()), [1, 1, 1])
+
+    def test_length_mismatch(self):
+        inc = IncrementalEchelon(2)
+        with self.assertRaises(ValueError):
+            inc.push_row([1, 0, 1])
+        inc.push_row([1, 0])
+        with self.assertRaises(ValueError):
+            inc.push_column([1, 0])
+
+
+if __name__ == '__main__':
+    unittest.main()
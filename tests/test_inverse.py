@@ -0,0 +1,40 @@
+import random
+import unittest
+
+from algebraic_immunity_utils import Matrix as GF2Matrix
+
+
+def identity(n):
+    return [[int(i == j) for j in range(n)] for i in range(n)]
+
+
+class TestInverse(unittest.TestCase):
+
+    def test_inverse_small(self):
+        m = GF2Matrix([[1, 1, 0], [0, 1, 0], [1, 1, 1]])
+        inv = m.inverse()
+        self.assertEqual([list(r) for r in inv.to_list()], [[1, 1, 0], [0, 1, 0], [1, 0, 1]])
+        self.assertEqual(m.det(), 1)
+
+    def test_inverse_random(self):
+        rng = random.Random(5)
+        for n in [2, 17, 65, 150]:
+            while True:
+                m = GF2Matrix([[rng.randint(0, 1) for _ in range(n)] for _ in range(n)])
+                if m.det() == 1:
+                    break
+            inv = m.inverse()
+            self.assertEqual([list(r) for r in (m @ inv).to_list()], identity(n))
+            self.assertEqual([list(r) for r in (inv @ m).to_list()], identity(n))
+
+    def test_singular(self):
+        m = GF2Matrix([[1, 1, 0], [0, 1, 1], [1, 0, 1]])
+        self.assertEqual(m.det(), 0)
+        with self.assertRaises(ValueError):
+            m.inverse()
+
+    def test_not_square(self):
+        with self.assertRaises(ValueError):
+            GF2Matrix([[1, 0, 1]]).inverse()
+        with self.assertRaises(ValueError):
+            GF2Matrix([[1, 0, 1]]).det()
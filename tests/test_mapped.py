@@ -0,0 +1,190 @@
+import os
+import itertools
+import random
+import struct
+import tempfile
+import threading
+import unittest
+
+from algebraic_immunity_utils import DimensionError, MappedMatrix, NonBinaryValueError
+from algebraic_immunity_utils import Matrix as GF2Matrix
+
+from helpers import random_rows, rows_of
+
+
+def graded_evaluation_rows(n):
+    monomials = [c for d in range(n + 1) for c in itertools.combinations(range(n), d)]
+    return [[int(all(p >> i & 1 for i in mono)) for p in range(2 ** n)] for mono in monomials]
+
+
+def write_legacy(path, rows, cols, values):
+    with open(path, 'wb') as f:
+        f.write(struct.pack('<II', rows, cols))
+        f.write(struct.pack('<%di' % len(values), *values))
+
+
+class TestMappedMatrix(unittest.TestCase):
+
+    def setUp(self):
+        self.dir = tempfile.TemporaryDirectory()
+        self.addCleanup(self.dir.cleanup)
+
+    def path(self, name):
+        return os.path.join(self.dir.name, name)
+
+    def test_round_trip(self):
+        m_l = random_rows(random.Random(1), 5, 70)
+        mapped = MappedMatrix.from_matrix(self.path('m.bin'), GF2Matrix(m_l))
+        mapped.set(4, 69, 1)
+        m_l[4][69] = 1
+        mapped.flush()
+        del mapped
+
+        mapped = MappedMatrix.open(self.path('m.bin'))
+        self.assertEqual((mapped.nrows(), mapped.ncols()), (5, 70))
+        self.assertEqual(rows_of(mapped.to_matrix()), m_l)
+        self.assertEqual(rows_of(mapped.get_sub_matrix(1, 3)), m_l[1:3])
+        self.assertEqual(mapped.get(4, 69), 1)
+
+    def test_open_rejects_other_files(self):
+        with open(self.path('junk.bin'), 'wb') as f:
+            f.write(b'\x00' * 100)
+        with self.assertRaises(ValueError):
+            MappedMatrix.open(self.path('junk.bin'))
+
+    def test_rank_and_kernel_match_matrix(self):
+        rng = random.Random(2)
+        for rows, cols in [(300, 200), (130, 250), (200, 200)]:
+            dependent = random_rows(rng, rows // 2, cols)
+            m_l = dependent + [[a ^ b for a, b in zip(r, dependent[0])] for r in dependent]
+            rng.shuffle(m_l)
+            m = GF2Matrix(m_l)
+            mapped = MappedMatrix.from_matrix(self.path('m.bin'), m)
+
+            self.assertEqual(mapped.rank(tile_rows=64), m.rank())
+            self.assertEqual(rows_of(mapped.to_matrix()), m_l)
+
+            kernel = mapped.kernel(self.path('k.bin'), tile_rows=64)
+            self.assertEqual([list(v) for v in rows_of(kernel.to_matrix())], [list(v) for v in m.kernel()])
+            self.assertEqual(sorted(os.listdir(self.dir.name)), ['k.bin', 'm.bin'])
+
+    def test_concurrent_rank(self):
+        m = GF2Matrix.random(300, 200, seed=3)
+        MappedMatrix.from_matrix(self.path('m.bin'), m)
+        handles = [MappedMatrix.open(self.path('m.bin')) for _ in range(4)]
+        ranks = [None] * len(handles)
+
+        def run(i):
+            ranks[i] = handles[i].rank(tile_rows=64)
+
+        threads = [threading.Thread(target=run, args=(i,)) for i in range(len(handles))]
+        for t in threads:
+            t.start()
+        for t in threads:
+            t.join()
+        self.assertEqual(ranks, [m.rank()] * len(handles))
+        self.assertEqual(os.listdir(self.dir.name), ['m.bin'])
+
+    def test_echelonize_in_place(self):
+        m_l = random_rows(random.Random(3), 150, 100, density=0.1)
+        mapped = MappedMatrix.from_matrix(self.path('m.bin'), GF2Matrix(m_l))
+        rank = mapped.echelonize(tile_rows=64)
+        self.assertEqual(rank, GF2Matrix(m_l).rank())
+        self.assertEqual(mapped.to_matrix().rank(), rank)
+
+
+class TestMatrixFile(unittest.TestCase):
+
+    def setUp(self):
+        self.dir = tempfile.TemporaryDirectory()
+        self.addCleanup(self.dir.cleanup)
+
+    def path(self, name):
+        return os.path.join(self.dir.name, name)
+
+    def test_convert_legacy(self):
+        m_l = graded_evaluation_rows(4)
+        write_legacy(self.path('old.bin'), 16, 16, [v for row in m_l for v in row])
+        mapped = MappedMatrix.convert_legacy(self.path('old.bin'), self.path('new.bin'))
+        self.assertEqual((mapped.monomial_order, mapped.n), ('graded', 4))
+        self.assertEqual(rows_of(mapped.to_matrix()), m_l)
+        del mapped
+        self.assertEqual(os.path.getsize(self.path('new.bin')), 64 + 16 * 8)
+        self.assertEqual(rows_of(GF2Matrix.load(self.path('new.bin'))), m_l)
+
+        mapped = MappedMatrix.convert_legacy(self.path('old.bin'), self.path('plain.bin'), monomial_order=None)
+        self.assertEqual((mapped.monomial_order, mapped.n), (None, None))
+
+    def test_convert_legacy_rejects_bad_files(self):
+        write_legacy(self.path('short.bin'), 2, 3, [0] * 5)
+        with self.assertRaises(ValueError):
+            MappedMatrix.convert_legacy(self.path('short.bin'), self.path('new.bin'))
+        write_legacy(self.path('wide.bin'), 2, 3, [0] * 6)
+        with self.assertRaises(DimensionError):
+            MappedMatrix.convert_legacy(self.path('wide.bin'), self.path('new.bin'))
+        write_legacy(self.path('bad.bin'), 1, 2, [1, 2])
+        with self.assertRaises(NonBinaryValueError):
+            MappedMatrix.convert_legacy(self.path('bad.bin'), self.path('new.bin'), monomial_order=None)
+        with self.assertRaises(ValueError):
+            GF2Matrix.load(self.path('bad.bin'))
+
+    def test_header(self):
+        mapped = MappedMatrix.create(self.path('m.gf2'), 5, 8, monomial_order='graded', n=3)
+        mapped.set(4, 7, 1)
+        del mapped
+        with open(self.path('m.gf2'), 'rb') as f:
+            header = f.read(64)
+        self.assertEqual(header[:8], b'GF2MATRX')
+        self.assertEqual(struct.unpack('<IIQQQ', header[8:40]), (2, 1, 5, 8, 3))
+        self.assertEqual(header[40:], bytes(24))
+        self.assertEqual(GF2Matrix.load(self.path('m.gf2')).get(4, 7), 1)
+
+        with self.assertRaises(ValueError):
+            MappedMatrix.create(self.path('x.bin'), 5, 8, monomial_order='graded')
+        with self.assertRaises(ValueError):
+            MappedMatrix.create(self.path('x.bin'), 5, 8, monomial_order='lex', n=3)
+        with self.assertRaises(DimensionError):
+            MappedMatrix.create(self.path('x.bin'), 5, 10, monomial_order='graded', n=3)
+
+    def test_version_1_files(self):
+        m = GF2Matrix.random(3, 70, seed=4)
+        m.save(self.path('m.bin'))
+        with open(self.path('m.bin'), 'r+b') as f:
+            f.seek(8)
+            f.write(struct.pack('<I', 1))
+        mapped = MappedMatrix.open(self.path('m.bin'))
+        self.assertEqual(mapped.to_matrix(), m)
+        self.assertIsNone(mapped.monomial_order)
+        with open(self.path('m.bin'), 'r+b') as f:
+            f.seek(8)
+            f.write(struct.pack('<I', 3))
+        with self.assertRaises(ValueError):
+            GF2Matrix.load(self.path('m.bin'))
+
+    def test_malformed_headers(self):
+        huge = b'GF2MATRX' + struct.pack('<IIQQQ', 2, 0, 2 ** 62, 2 ** 62, 0) + bytes(24)
+        cases = [
+            ('huge.gf2', huge),
+            ('short.gf2', huge[:40]),
+            ('long.gf2', huge[:16] + struct.pack('<QQ', 1, 1) + bytes(41)),
+        ]
+        for name, data in cases:
+            with open(self.path(name), 'wb') as f:
+                f.write(data)
+            with self.assertRaises(ValueError, msg=name):
+                GF2Matrix.load(self.path(name))
+            with self.assertRaises(ValueError, msg=name):
+                MappedMatrix.open(self.path(name))
+        with self.assertRaises(DimensionError):
+            MappedMatrix.create(self.path('x.gf2'), 2 ** 62, 2 ** 62)
+        self.assertFalse(os.path.exists(self.path('x.gf2')))
+
+    def test_save_and_load(self):
+        for m in [GF2Matrix.random(20, 130, seed=6), GF2Matrix.zeros(0, 5), GF2Matrix.zeros(4, 0)]:
+            m.save(self.path('m.gf2'))
+            self.assertEqual(GF2Matrix.load(self.path('m.gf2')), m)
+            self.assertEqual(MappedMatrix.open(self.path('m.gf2')).to_matrix(), m)
+
+
+if __name__ == '__main__':
+    unittest.main()
@@ -0,0 +1,48 @@
+import random
+import unittest
+
+from algebraic_immunity_utils import Matrix as GF2Matrix
+
+from helpers import random_rows
+
+
+def naive_product(a, b):
+    return [
+        [sum(a[i][k] & b[k][j] for k in range(len(b))) % 2 for j in range(len(b[0]))]
+        for i in range(len(a))
+    ]
+
+
+class TestMultiplication(unittest.TestCase):
+
+    def test_matmul_small(self):
+        a = GF2Matrix([[1, 1, 0], [0, 1, 1]])
+        b = GF2Matrix([[1, 0], [1, 1], [0, 1]])
+        self.assertEqual([list(r) for r in (a @ b).to_list()], [[0, 1], [1, 0]])
+
+    def test_matmul_matches_naive_product(self):
+        rng = random.Random(7)
+        for m, n, p in [(3, 5, 4), (70, 65, 130), (150, 90, 70)]:
+            a_l, b_l = random_rows(rng, m, n), random_rows(rng, n, p)
+            c = GF2Matrix(a_l) @ GF2Matrix(b_l)
+            self.assertEqual([list(r) for r in c.to_list()], naive_product(a_l, b_l))
+
+    def test_matmul_large_blocks(self):
+        rng = random.Random(11)
+        a_l, b_l = random_rows(rng, 1100, 1030), random_rows(rng, 1030, 1050)
+        a, b = GF2Matrix(a_l), GF2Matrix(b_l)
+        c = a @ b
+        self.assertEqual((c.nrows(), c.ncols()), (1100, 1050))
+        for _ in range(4):
+            x = [rng.randint(0, 1) for _ in range(1050)]
+            self.assertEqual(c.mul_vec(x), a.mul_vec(list(b.mul_vec(x))))
+
+    def test_mul_vec(self):
+        m = GF2Matrix([[1, 0, 1], [1, 1, 1]])
+        self.assertEqual(list(m.mul_vec([1, 1, 0])), [1, 0])
+
+    def test_dimension_mismatch(self):
+        with self.assertRaises(ValueError):
+            GF2Matrix([[1, 0]]) @ GF2Matrix([[1, 0]])
+        with self.assertRaises(ValueError):
+            GF2Matrix([[1, 0]]).mul_vec([1])
@@ -0,0 +1,105 @@
+import random
+import unittest
+
+from algebraic_immunity_utils import Matrix as GF2Matrix
+
+from helpers import rows_of
+
+try:
+    import numpy as np
+except ImportError:
+    np = None
+
+
+def packbits(row, bitorder='big'):
+    out = []
+    for b in range(0, len(row), 8):
+        chunk = row[b:b + 8] + [0] * (8 - len(row[b:b + 8]))
+        if bitorder == 'big':
+            chunk = chunk[::-1]
+        out.append(sum(bit << i for i, bit in enumerate(chunk)))
+    return out
+
+
+class TestBufferProtocol(unittest.TestCase):
+
+    def setUp(self):
+        rng = random.Random(5)
+        self.m_l = [[rng.randint(0, 1) for _ in range(13)] for _ in range(4)]
+
+    def test_from_byte_buffer(self):
+        data = bytes(v for row in self.m_l for v in row)
+        view = memoryview(data).cast('B', shape=[4, 13])
+        self.assertEqual(rows_of(GF2Matrix.from_numpy(view)), self.m_l)
+
+    def test_from_packed_buffer(self):
+        for bitorder in ['big', 'little']:
+            data = bytes(b for row in self.m_l for b in packbits(row, bitorder))
+            view = memoryview(data).cast('B', shape=[4, 2])
+            m = GF2Matrix.from_numpy(view, ncols=13, bitorder=bitorder)
+            self.assertEqual(rows_of(m), self.m_l)
+
+    def test_rejects_bad_buffers(self):
+        with self.assertRaises(ValueError):
+            GF2Matrix.from_numpy(memoryview(bytes([0, 2, 1, 0])).cast('B', shape=[2, 2]))
+        with self.assertRaises(ValueError):
+            GF2Matrix.from_numpy(memoryview(bytes(4)))
+        with self.assertRaises(ValueError):
+            GF2Matrix.from_numpy(memoryview(bytes(4)).cast('B', shape=[2, 2]), ncols=20)
+        with self.assertRaises(ValueError):
+            GF2Matrix.from_numpy(memoryview(bytes(4)).cast('B', shape=[2, 2]), ncols=9, bitorder='middle')
+
+    def test_packed_rows_view(self):
+        m = GF2Matrix(self.m_l)
+        view = memoryview(m.packed_rows())
+        self.assertTrue(view.readonly)
+        self.assertEqual(view.shape, (4, 2))
+        self.assertEqual(view.strides, (8, 1))
+        self.assertEqual(view.tolist(), [packbits(row, 'little') for row in self.m_l])
+        self.assertEqual(bytes(m.packed_rows()), m.to_bytes())
+        self.assertEqual(rows_of(GF2Matrix.from_numpy(view, ncols=13, bitorder='little')), self.m_l)
+
+    def test_packed_rows_outlive_changes(self):
+        m = GF2Matrix(self.m_l)
+        view = memoryview(m.packed_rows())
+        m[0, 0] = 1 - self.m_l[0][0]
+        del m
+        self.assertEqual(view.tolist(), [packbits(row, 'little') for row in self.m_l])
+
+    def test_packed_rows_of_empty_matrices(self):
+        self.assertEqual(memoryview(GF2Matrix.zeros(3, 0).packed_rows()).shape, (3, 0))
+        self.assertEqual(bytes(GF2Matrix.zeros(0, 5).packed_rows()), b'')
+
+
+@unittest.skipIf(np is None, 'numpy is not installed')
+class TestNumpy(unittest.TestCase):
+
+    def test_round_trip(self):
+        rng = np.random.default_rng(6)
+        a = rng.integers(0, 2, size=(50, 77), dtype=np.uint8)
+        m = GF2Matrix.from_numpy(a)
+        self.assertTrue(np.array_equal(m.to_numpy(), a))
+        self.assertTrue(np.array_equal(np.asarray(m), a))
+        self.assertTrue(np.array_equal(GF2Matrix.from_numpy(a[:, ::2]).to_numpy(), a[:, ::2]))
+
+    def test_packbits(self):
+        rng = np.random.default_rng(7)
+        a = rng.integers(0, 2, size=(20, 70), dtype=np.uint8)
+        for bitorder in ['big', 'little']:
+            packed = np.packbits(a, axis=1, bitorder=bitorder)
+            m = GF2Matrix.from_numpy(packed, ncols=70, bitorder=bitorder)
+            self.assertTrue(np.array_equal(m.to_numpy(), a))
+            self.assertTrue(np.array_equal(m.to_numpy(packed=True, bitorder=bitorder), packed))
+
+    def test_packed_rows_share_memory(self):
+        rng = np.random.default_rng(8)
+        a = rng.integers(0, 2, size=(20, 70), dtype=np.uint8)
+        m = GF2Matrix.from_numpy(a)
+        view = np.asarray(m.packed_rows())
+        self.assertTrue(np.array_equal(view, np.packbits(a, axis=1, bitorder='little')))
+        self.assertTrue(np.shares_memory(view, np.asarray(m.packed_rows())))
+        self.assertFalse(view.flags.writeable)
+
+
+if __name__ == '__main__':
+    unittest.main()
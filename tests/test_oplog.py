@@ -0,0 +1,54 @@
+import random
+import unittest
+
+from algebraic_immunity_utils import Matrix as GF2Matrix
+from algebraic_immunity_utils import OpLog, RowOp
+
+
+class TestOpLog(unittest.TestCase):
+
+    def test_row_op_variants(self):
+        swap = RowOp.Swap(row1=0, row2=2)
+        add = RowOp.AddRow(target=1, source=0)
+        self.assertEqual((swap.row1, swap.row2), (0, 2))
+        self.assertEqual((add.target, add.source), (1, 0))
+        self.assertEqual(swap, RowOp.Swap(row1=0, row2=2))
+        self.assertNotEqual(swap, add)
+
+    def test_apply(self):
+        log = OpLog([RowOp.Swap(row1=0, row2=1), RowOp.AddRow(target=2, source=0)])
+        self.assertEqual(list(log.apply_to_vector([1, 0, 1])), [0, 1, 1])
+        m = GF2Matrix([[1, 0], [0, 1], [1, 1]])
+        self.assertEqual([list(r) for r in log.apply_to_matrix(m).to_list()], [[0, 1], [1, 0], [1, 0]])
+        self.assertEqual((log.transform(3) @ m).to_list(), log.apply_to_matrix(m).to_list())
+        with self.assertRaises(ValueError):
+            log.apply_to_vector([1, 0])
+
+    def test_elimination_log_replays(self):
+        rng = random.Random(41)
+        m = GF2Matrix([[rng.randint(0, 1) for _ in range(7)] for _ in range(6)])
+        reduced, log = m.echelon_form()
+        self.assertEqual(log.apply_to_matrix(m).to_list(), reduced.to_list())
+        self.assertEqual(log.inverse().apply_to_matrix(reduced).rank(), m.rank())
+
+    def test_compose_and_inverse(self):
+        a = OpLog.from_pairs([(0, 1), (2, 1)])
+        b = OpLog([RowOp.Swap(row1=0, row2=2)])
+        v = [1, 1, 0]
+        self.assertEqual(a.compose(b).apply_to_vector(v), b.apply_to_vector(a.apply_to_vector(v)))
+        self.assertEqual(a.compose(b).inverse().apply_to_vector(a.compose(b).apply_to_vector(v)), bytes(v))
+
+    def test_compact(self):
+        log = OpLog.from_pairs([(0, 1), (1, 0), (0, 1), (2, 0), (2, 0)])
+        log.swap(1, 1)
+        compact = log.compact()
+        self.assertEqual(compact.to_list(), [RowOp.Swap(row1=0, row2=1)])
+        self.assertEqual(compact.to_pairs(), [(0, 1), (1, 0), (0, 1)])
+        v = [1, 0, 1]
+        self.assertEqual(compact.apply_to_vector(v), log.apply_to_vector(v))
+
+    def test_sequence_protocol(self):
+        log = OpLog.from_pairs([(0, 1), (1, 0)])
+        self.assertEqual(len(log), 2)
+        self.assertEqual(log[-1], RowOp.AddRow(target=1, source=0))
+        self.assertEqual(list(log), log.to_list())
@@ -0,0 +1,48 @@
+import random
+import unittest
+
+from algebraic_immunity_utils import Matrix as GF2Matrix
+
+
+def as_lists(m):
+    return [list(row) for row in m.to_list()]
+
+
+class TestPLUQ(unittest.TestCase):
+
+    def test_factors_reproduce_matrix(self):
+        rng = random.Random(4)
+        for rows, cols in [(1, 1), (4, 6), (6, 4), (70, 70), (90, 130)]:
+            m_l = [[rng.randint(0, 1) for _ in range(cols)] for _ in range(rows)]
+            m_l[-1] = list(m_l[0])
+            m = GF2Matrix(m_l)
+            f = m.pluq()
+            self.assertEqual(f.rank, m.rank())
+            self.assertEqual((f.l.nrows(), f.l.ncols()), (rows, f.rank))
+            self.assertEqual((f.u.nrows(), f.u.ncols()), (f.rank, cols))
+            for i in range(f.rank):
+                self.assertEqual(f.l.get(i, i), 1)
+                self.assertEqual(f.u.get(i, i), 1)
+                self.assertTrue(all(f.l.get(i, j) == 0 for j in range(i + 1, f.rank)))
+                self.assertTrue(all(f.u.get(i, j) == 0 for j in range(i)))
+            if f.rank:
+                self.assertEqual(as_lists(f.p() @ f.l @ f.u @ f.q()), m_l)
+
+    def test_row_rank_profile(self):
+        m = GF2Matrix([[0, 0, 0], [1, 1, 0], [1, 1, 0], [0, 1, 1], [1, 0, 1]])
+        f = m.pluq()
+        self.assertEqual(f.rank, 2)
+        self.assertEqual(f.row_rank_profile(), [1, 3])
+
+    def test_solve(self):
+        rng = random.Random(9)
+        m_l = [[rng.randint(0, 1) for _ in range(12)] for _ in range(9)]
+        m = GF2Matrix(m_l)
+        f = m.pluq()
+        for _ in range(20):
+            x = [rng.randint(0, 1) for _ in range(12)]
+            b = list(m.mul_vec(x))
+            self.assertEqual(list(m.mul_vec(list(f.solve(b)))), b)
+
+        inconsistent = GF2Matrix([[1, 1], [1, 1]]).pluq()
+        self.assertIsNone(inconsistent.solve([1, 0]))
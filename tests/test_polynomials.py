@@ -0,0 +1,101 @@
+import random
+import unittest
+
+from algebraic_immunity_utils import DimensionError
+from algebraic_immunity_utils import Matrix as GF2Matrix
+
+from helpers import companion, rows_of
+
+
+def evaluate(coefficients, m):
+    n = m.nrows()
+    result = [[0] * n for _ in range(n)]
+    power = GF2Matrix.identity(n)
+    for c in coefficients:
+        if c:
+            result = [[a ^ b for a, b in zip(r, p)] for r, p in zip(result, rows_of(power))]
+        power = power @ m
+    return result
+
+
+def poly_mul(p, q):
+    out = [0] * (len(p) + len(q) - 1)
+    for i, a in enumerate(p):
+        for j, b in enumerate(q):
+            out[i + j] ^= a & b
+    return out
+
+
+def minpoly_degree(m):
+    n = m.nrows()
+    power, flattened = GF2Matrix.identity(n), []
+    for d in range(n + 1):
+        flattened.append([v for r in rows_of(power) for v in r])
+        if GF2Matrix(flattened).rank() == d:
+            return d
+        power = power @ m
+
+
+class TestPolynomials(unittest.TestCase):
+
+    def test_lfsr_companion(self):
+        feedback = [1, 1, 0, 0, 1]  # x^4 + x + 1
+        c = companion(feedback)
+        self.assertEqual(list(c.charpoly()), feedback)
+        self.assertEqual(list(c.minpoly()), feedback)
+        self.assertEqual([list(f) for f in c.invariant_factors()], [feedback])
+        self.assertEqual(c.frobenius_form(), c)
+
+    def test_identity_and_zero(self):
+        self.assertEqual(list(GF2Matrix.identity(3).charpoly()), [1, 1, 1, 1])
+        self.assertEqual(list(GF2Matrix.identity(3).minpoly()), [1, 1])
+        self.assertEqual([list(f) for f in GF2Matrix.zeros(2, 2).invariant_factors()], [[0, 1], [0, 1]])
+        self.assertEqual(list(GF2Matrix.zeros(0, 0).charpoly()), [1])
+        with self.assertRaises(DimensionError):
+            GF2Matrix.zeros(2, 3).charpoly()
+
+    def test_random_matrices(self):
+        rng = random.Random(9)
+        for n in [1, 2, 5, 17, 40]:
+            for _ in range(3):
+                m = GF2Matrix([[rng.randint(0, 1) for _ in range(n)] for _ in range(n)])
+                chi, mu = list(m.charpoly()), list(m.minpoly())
+                self.assertEqual((len(chi), chi[-1]), (n + 1, 1))
+                self.assertEqual(evaluate(chi, m), [[0] * n] * n)
+                self.assertEqual(evaluate(mu, m), [[0] * n] * n)
+                self.assertEqual(len(mu) - 1, minpoly_degree(m))
+
+    def test_structured_matrices(self):
+        # Several equal blocks and a nilpotent part make the maximal vector non-trivial.
+        blocks = [companion([1, 1, 1]), companion([1, 1, 1]), companion([1, 0, 1]), companion([0, 0, 1]),
+                  companion([0, 1]), GF2Matrix.identity(2)]
+        n = sum(b.nrows() for b in blocks)
+        rows, offset = [], 0
+        for b in blocks:
+            for r in rows_of(b):
+                rows.append([0] * offset + r + [0] * (n - offset - b.nrows()))
+            offset += b.nrows()
+        p = GF2Matrix.random_invertible(n, seed=2)
+        m = p @ GF2Matrix(rows) @ p.inverse()
+        factors = [list(f) for f in m.invariant_factors()]
+        product = [1]
+        for f in factors:
+            product = poly_mul(product, f)
+        self.assertEqual(list(m.charpoly()), product)
+        self.assertEqual(factors[-1], list(m.minpoly()))
+        self.assertEqual(len(factors[-1]) - 1, minpoly_degree(m))
+        p = [1, 1, 1]
+        self.assertEqual(factors, [[1, 1], poly_mul(poly_mul(p, [1, 1]), [0, 1]),
+                                   poly_mul(poly_mul(p, [1, 0, 1]), [0, 0, 1])])
+
+    def test_frobenius_is_a_similarity_invariant(self):
+        m = GF2Matrix.random(30, 30, seed=4)
+        p = GF2Matrix.random_invertible(30, seed=5)
+        form = m.frobenius_form()
+        self.assertEqual((p @ m @ p.inverse()).frobenius_form(), form)
+        self.assertEqual(form.frobenius_form(), form)
+        self.assertEqual(list(form.charpoly()), list(m.charpoly()))
+
+
+if __name__ == '__main__':
+    unittest.main()
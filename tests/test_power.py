@@ -0,0 +1,45 @@
+import unittest
+
+from algebraic_immunity_utils import DimensionError
+from algebraic_immunity_utils import Matrix as GF2Matrix
+
+from helpers import companion
+
+
+class TestPower(unittest.TestCase):
+
+    def setUp(self):
+        self.m = GF2Matrix.random(20, 20, seed=3)
+
+    def test_small_exponents(self):
+        power = GF2Matrix.identity(20)
+        for k in range(10):
+            self.assertEqual(self.m.pow(k), power)
+            self.assertEqual(self.m ** k, power)
+            power = power @ self.m
+        with self.assertRaises(ValueError):
+            self.m.pow(-1)
+        with self.assertRaises(DimensionError):
+            GF2Matrix.zeros(2, 3).pow(2)
+
+    def test_lfsr_period(self):
+        # x^4 + x + 1 is primitive, so the LFSR state repeats every 15 clocks.
+        c = companion([1, 1, 0, 0, 1])
+        self.assertEqual(c.pow(15), GF2Matrix.identity(4))
+        self.assertEqual(c.pow(2 ** 60), c.pow(2 ** 60 % 15))
+        self.assertEqual(list(c.apply_pow([1, 0, 0, 0], 2 ** 100 + 3)), list(c.apply_pow([1, 0, 0, 0], (2 ** 100 + 3) % 15)))
+
+    def test_apply_pow(self):
+        v = [1, 0, 1, 1] * 5
+        for k in [0, 1, 2, 7, 123, 2 ** 60, 2 ** 64 + 5, 3 ** 90]:
+            expected = self.m.pow(k).mul_vec(v)
+            self.assertEqual(list(self.m.apply_pow(v, k)), list(expected))
+        a, b = 2 ** 61 + 17, 3 ** 50
+        self.assertEqual(list(self.m.apply_pow(self.m.apply_pow(v, a), b)), list(self.m.apply_pow(v, a + b)))
+        self.assertEqual(list(self.m.apply_pow([0] * 20, 2 ** 70)), [0] * 20)
+        with self.assertRaises(DimensionError):
+            self.m.apply_pow([1, 0], 3)
+
+
+if __name__ == '__main__':
+    unittest.main()
@@ -0,0 +1,64 @@
+import copy
+import pickle
+import random
+import unittest
+from concurrent.futures import ProcessPoolExecutor
+
+from algebraic_immunity_utils import Matrix as GF2Matrix
+
+
+def rank_of(m):
+    return m.rank()
+
+
+class TestProtocol(unittest.TestCase):
+
+    def setUp(self):
+        rng = random.Random(8)
+        self.m_l = [[rng.randint(0, 1) for _ in range(70)] for _ in range(5)]
+        self.m = GF2Matrix(self.m_l)
+
+    def test_equality(self):
+        self.assertEqual(self.m, GF2Matrix(self.m_l))
+        self.assertNotEqual(self.m, GF2Matrix(self.m_l[:4]))
+        self.assertNotEqual(GF2Matrix.zeros(0, 2), GF2Matrix.zeros(0, 3))
+        self.assertNotEqual(self.m, self.m_l)
+        with self.assertRaises(TypeError):
+            hash(self.m)
+
+    def test_len_and_iter(self):
+        self.assertEqual(len(self.m), 5)
+        self.assertEqual(list(self.m), self.m_l)
+        self.assertEqual(list(GF2Matrix.zeros(0, 3)), [])
+
+    def test_setitem(self):
+        m = self.m.copy()
+        m[1, -1] = 1 - self.m_l[1][-1]
+        self.assertEqual(m[1, 69], 1 - self.m_l[1][69])
+        self.assertNotEqual(m, self.m)
+        with self.assertRaises(IndexError):
+            m[5, 0] = 1
+        with self.assertRaises(ValueError):
+            m[0, 0] = 2
+
+    def test_copy(self):
+        for c in [copy.copy(self.m), copy.deepcopy(self.m)]:
+            self.assertEqual(c, self.m)
+            c[0, 0] = 1 - c[0, 0]
+            self.assertNotEqual(c, self.m)
+
+    def test_pickle(self):
+        for m in [self.m, GF2Matrix.zeros(3, 0), GF2Matrix.zeros(0, 0)]:
+            for protocol in range(pickle.HIGHEST_PROTOCOL + 1):
+                restored = pickle.loads(pickle.dumps(m, protocol=protocol))
+                self.assertEqual(restored, m)
+                self.assertEqual((restored.nrows(), restored.ncols()), (m.nrows(), m.ncols()))
+
+    def test_multiprocessing(self):
+        matrices = [GF2Matrix.random_of_rank(20, 30, r, seed=r) for r in range(4)]
+        with ProcessPoolExecutor(max_workers=2) as pool:
+            self.assertEqual(list(pool.map(rank_of, matrices)), [0, 1, 2, 3])
+
+
+if __name__ == '__main__':
+    unittest.main()
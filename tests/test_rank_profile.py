@@ -0,0 +1,107 @@
+import random
+import unittest
+
+from algebraic_immunity_utils import DimensionError
+from algebraic_immunity_utils import Matrix as GF2Matrix
+
+from helpers import rows_of
+
+
+def greedy_profile(rows):
+    chosen = []
+    for i in range(len(rows)):
+        if GF2Matrix([rows[j] for j in chosen + [i]]).rank() == len(chosen) + 1:
+            chosen.append(i)
+    return chosen
+
+
+class TestRankProfile(unittest.TestCase):
+
+    def setUp(self):
+        rng = random.Random(12)
+        self.matrices = [GF2Matrix.random_of_rank(rows, cols, rank, seed=rank)
+                         for rows, cols, rank in [(8, 10, 5), (12, 6, 4), (70, 90, 40)]]
+        self.matrices.append(GF2Matrix([[0, 1, 1, 0], [0, 1, 1, 0], [0, 0, 0, 1]]))
+        self.rng = rng
+
+    def test_rank_profile(self):
+        for m in self.matrices:
+            rows, cols = m.rank_profile()
+            self.assertEqual(rows, greedy_profile(rows_of(m)))
+            self.assertEqual(cols, greedy_profile(rows_of(m.transpose())))
+        self.assertEqual(GF2Matrix([[0, 1, 1, 0], [0, 1, 1, 0], [0, 0, 0, 1]]).rank_profile(), ([0, 2], [1, 3]))
+
+    def test_structured_result(self):
+        m = GF2Matrix([[0, 1, 1, 0], [0, 1, 1, 0], [0, 0, 0, 1]])
+        result = m.echelon_form()
+        self.assertEqual((result.pivots, result.free_columns, result.rank), ([1, 3], [0, 2], 2))
+        reduced, ops = result
+        self.assertEqual(reduced, result.reduced)
+        self.assertEqual(len(result), 2)
+        self.assertIsNone(result.transform)
+        self.assertEqual(result[-1].to_pairs(), ops.to_pairs())
+        with self.assertRaises(IndexError):
+            result[2]
+        full = m.row_echelon_full_matrix(with_transform=True)
+        self.assertEqual(len(full), 3)
+        self.assertEqual(full.transform @ m, full.reduced)
+        self.assertEqual(repr(result), 'EchelonForm(rank=2, pivots=[1, 3], free_columns=[0, 2])')
+
+    def test_pivots_match_rank_profile(self):
+        for m in self.matrices:
+            for method in ['echelon_form', 'row_echelon_full_matrix', 'echelon_form_m4ri']:
+                result = getattr(m, method)()
+                self.assertEqual(result.pivots, m.rank_profile()[1])
+                self.assertEqual(result.rank, m.rank())
+                self.assertEqual(sorted(result.pivots + result.free_columns), list(range(m.ncols())))
+
+    def test_column_echelon_form(self):
+        for m in self.matrices:
+            result = m.column_echelon_form(with_transform=True)
+            self.assertEqual(result.reduced, m.transpose().echelon_form()[0].transpose())
+            self.assertEqual(m @ result.transform, result.reduced)
+            self.assertEqual(result.pivots, m.rank_profile()[0])
+            self.assertEqual(result.free_columns, list(range(result.rank, m.ncols())))
+            kernel = result.transform.select_columns(result.free_columns)
+            self.assertEqual((m @ kernel).weight(), 0)
+            self.assertEqual(kernel.transpose().rank(), m.ncols() - m.rank())
+            reduced, ops = m.column_echelon_form()
+            self.assertEqual(reduced, result.reduced)
+
+
+class TestRankOnly(unittest.TestCase):
+
+    def test_rank_in_place(self):
+        for rows, cols, rank in [(10, 12, 6), (200, 180, 150)]:
+            m = GF2Matrix.random_of_rank(rows, cols, rank, seed=1)
+            reference = m.copy()
+            self.assertEqual(m.rank_in_place(), rank)
+            self.assertEqual(m.rank(), rank)
+            self.assertEqual(m.echelon_form()[0], reference.echelon_form()[0])
+
+    def test_rank_of_rows(self):
+        m = GF2Matrix.random_of_rank(30, 20, 13, seed=2)
+        self.assertEqual(GF2Matrix.rank_of_rows(iter(m), 20), 13)
+        self.assertEqual(GF2Matrix.rank_of_rows([], 5), 0)
+        with self.assertRaises(DimensionError):
+            GF2Matrix.rank_of_rows([[1, 0], [1]], 2)
+        with self.assertRaises(TypeError):
+            GF2Matrix.rank_of_rows(5, 2)
+
+    def test_vandermonde_full_rank_stops_early(self):
+        support = [format(i, '04b') for i in range(16)]
+        monomials = [format(i, '04b') for i in [0, 1, 2, 4, 8]]
+        consumed = []
+
+        def rows():
+            for z in support:
+                consumed.append(z)
+                yield [int(all(zc >= ec for zc, ec in zip(z, e))) for e in monomials]
+
+        self.assertEqual(GF2Matrix.rank_of_rows(rows(), len(monomials)), len(monomials))
+        self.assertLess(len(consumed), len(support))
+        self.assertEqual(GF2Matrix(GF2Matrix.compute_vandermonde(support, monomials)).rank(), len(monomials))
+
+
+if __name__ == '__main__':
+    unittest.main()
@@ -0,0 +1,70 @@
+import json
+import pickle
+import unittest
+
+from algebraic_immunity_utils import OpLog, RestrictedAI
+from algebraic_immunity_utils import Matrix as GF2Matrix
+
+
+@unittest.skipUnless(hasattr(GF2Matrix, 'to_json'), 'built without the serde feature')
+class TestSerialize(unittest.TestCase):
+
+    def setUp(self):
+        self.m = GF2Matrix([[1, 0, 1], [0, 1, 1]])
+
+    def test_matrix_json(self):
+        self.assertEqual(json.loads(self.m.to_json()), {'ncols': 3, 'rows': ['101', '011']})
+        for m in [self.m, GF2Matrix.random(40, 130, seed=1), GF2Matrix.zeros(0, 7), GF2Matrix.zeros(3, 0)]:
+            self.assertEqual(GF2Matrix.from_json(m.to_json()), m)
+
+    def test_matrix_cbor(self):
+        for m in [self.m, GF2Matrix.random(40, 130, seed=1), GF2Matrix.zeros(0, 7), GF2Matrix.zeros(3, 0)]:
+            self.assertEqual(GF2Matrix.from_cbor(m.to_cbor()), m)
+        big = GF2Matrix.random(200, 200, seed=2)
+        self.assertIn(big.to_bytes(), big.to_cbor())
+        self.assertLess(len(big.to_cbor()), len(pickle.dumps(big.to_list())) // 5)
+
+    def test_rejects_malformed_matrices(self):
+        bad = [
+            '{"ncols": 3, "rows": ["101", "01"]}',
+            '{"ncols": 2, "rows": ["12"]}',
+            '{"rows": ["1"]}',
+            'not json',
+        ]
+        for text in bad:
+            with self.assertRaises(ValueError, msg=text):
+                GF2Matrix.from_json(text)
+        with self.assertRaises(ValueError):
+            GF2Matrix.from_cbor(b'\xa0')
+        with self.assertRaises(ValueError):
+            GF2Matrix.from_cbor(self.m.to_cbor()[:-1])
+
+    def test_results(self):
+        echelon = GF2Matrix.random(30, 20, seed=3).echelon_form(with_transform=True)
+        for decoded in [type(echelon).from_json(echelon.to_json()), type(echelon).from_cbor(echelon.to_cbor())]:
+            self.assertEqual(decoded.reduced, echelon.reduced)
+            self.assertEqual(decoded.operations, echelon.operations)
+            self.assertEqual(decoded.transform, echelon.transform)
+            self.assertEqual((decoded.pivots, decoded.free_columns), (echelon.pivots, echelon.free_columns))
+
+        pluq = GF2Matrix.random(20, 30, seed=4).pluq()
+        decoded = type(pluq).from_cbor(pluq.to_cbor())
+        self.assertEqual((decoded.row_perm, decoded.col_perm, decoded.rank), (pluq.row_perm, pluq.col_perm, pluq.rank))
+        self.assertEqual((decoded.l, decoded.u), (pluq.l, pluq.u))
+
+        operations = GF2Matrix.random(10, 10, seed=5).echelon_form().operations
+        self.assertEqual(OpLog.from_json(operations.to_json()), operations)
+
+    def test_restricted_ai(self):
+        truth_table = [0, 1, 1, 0, 1, 0, 0, 1]
+        subset = [0, 1, 2, 3, 5, 6]
+        for decoded in [RestrictedAI.from_json(RestrictedAI(truth_table).to_json()),
+                        RestrictedAI.from_cbor(RestrictedAI(truth_table).to_cbor())]:
+            self.assertEqual(decoded.to_json(), '{"truth_table":[0,1,1,0,1,0,0,1]}')
+            self.assertEqual(decoded.compute_z(subset, 3), RestrictedAI(truth_table).compute_z(subset, 3))
+        with self.assertRaises(ValueError):
+            RestrictedAI.from_json('{"truth_table": [0, 2]}')
+
+
+if __name__ == '__main__':
+    unittest.main()
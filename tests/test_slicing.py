@@ -0,0 +1,67 @@
+import random
+import unittest
+
+from algebraic_immunity_utils import Matrix as GF2Matrix
+
+from helpers import rows_of
+
+
+class TestSlicing(unittest.TestCase):
+
+    def setUp(self):
+        rng = random.Random(4)
+        self.m_l = [[rng.randint(0, 1) for _ in range(70)] for _ in range(6)]
+        self.m = GF2Matrix(self.m_l)
+
+    def test_entries(self):
+        self.assertEqual(self.m[2, 65], self.m_l[2][65])
+        self.assertEqual(self.m[-1, -1], self.m_l[-1][-1])
+        with self.assertRaises(IndexError):
+            self.m[6, 0]
+        with self.assertRaises(TypeError):
+            self.m['a']
+
+    def test_rows_and_columns(self):
+        self.assertEqual(rows_of(self.m[1:4]), self.m_l[1:4])
+        self.assertEqual(rows_of(self.m[::-2]), self.m_l[::-2])
+        self.assertEqual(rows_of(self.m[[5, 0, 0]]), [self.m_l[5], self.m_l[0], self.m_l[0]])
+        self.assertEqual(rows_of(self.m[:, 60:70]), [r[60:70] for r in self.m_l])
+        self.assertEqual(rows_of(self.m[1:3, [69, 3, -2]]), [[r[69], r[3], r[-2]] for r in self.m_l[1:3]])
+        self.assertEqual(rows_of(self.m.select_columns([0, 64, 2])), [[r[0], r[64], r[2]] for r in self.m_l])
+
+    def test_single_rows(self):
+        self.assertEqual(self.m[2], self.m_l[2])
+        self.assertEqual(self.m[-1], self.m_l[-1])
+        self.assertEqual(self.m[0, 1:5], self.m_l[0][1:5])
+        self.assertEqual(self.m[3, [69, 0, -2]], [self.m_l[3][69], self.m_l[3][0], self.m_l[3][-2]])
+        self.assertEqual([self.m[i] for i in range(len(self.m))], list(self.m))
+        self.assertEqual(rows_of(self.m[2:3]), [self.m_l[2]])
+        with self.assertRaises(IndexError):
+            self.m[-7]
+
+    def test_stacking(self):
+        a = GF2Matrix([[1, 0], [0, 1]])
+        b = GF2Matrix([[1, 1, 1], [0, 0, 1]])
+        self.assertEqual(rows_of(GF2Matrix.hstack([a, b, a])), [[1, 0, 1, 1, 1, 1, 0], [0, 1, 0, 0, 1, 0, 1]])
+        self.assertEqual(rows_of(GF2Matrix.vstack([a, a[:1]])), [[1, 0], [0, 1], [1, 0]])
+        with self.assertRaises(ValueError):
+            GF2Matrix.vstack([a, b])
+        with self.assertRaises(ValueError):
+            GF2Matrix.hstack([a, b[:1]])
+        with self.assertRaises(ValueError):
+            GF2Matrix.hstack([])
+
+    def test_transpose(self):
+        self.assertEqual(rows_of(self.m.transpose()), [list(c) for c in zip(*self.m_l)])
+
+    def test_kron(self):
+        a = GF2Matrix([[1, 0], [1, 1]])
+        b = GF2Matrix([[0, 1, 1]])
+        self.assertEqual(rows_of(a.kron(b)), [[0, 1, 1, 0, 0, 0], [0, 1, 1, 0, 1, 1]])
+        k = self.m[:2, :40].kron(self.m[:3, :30])
+        self.assertEqual((k.nrows(), k.ncols()), (6, 1200))
+        self.assertEqual(k[4, 35 * 30 + 7], self.m_l[1][35] & self.m_l[1][7])
+
+
+if __name__ == '__main__':
+    unittest.main()
@@ -0,0 +1,82 @@
+import itertools
+import random
+import unittest
+
+from algebraic_immunity_utils import Matrix as GF2Matrix
+
+from helpers import span
+
+
+class TestSolve(unittest.TestCase):
+
+    def test_solution_space_is_complete(self):
+        rng = random.Random(12)
+        for rows, cols in [(3, 5), (5, 5), (6, 4)]:
+            m_l = [[rng.randint(0, 1) for _ in range(cols)] for _ in range(rows)]
+            m = GF2Matrix(m_l)
+            b = [rng.randint(0, 1) for _ in range(rows)]
+            expected = {
+                x for x in itertools.product([0, 1], repeat=cols)
+                if list(m.mul_vec(list(x))) == b
+            }
+            result = m.solve(b)
+            if not expected:
+                self.assertIsNone(result)
+                continue
+            particular, basis = result
+            self.assertEqual(len(basis), cols - m.rank())
+            solutions = {tuple(p ^ v for p, v in zip(particular, k)) for k in span([list(v) for v in basis], cols)}
+            self.assertEqual(solutions, expected)
+
+    def test_inconsistent(self):
+        m = GF2Matrix([[1, 0], [1, 0]])
+        self.assertIsNone(m.solve([1, 0]))
+
+    def test_solve_many(self):
+        rng = random.Random(13)
+        m_l = [[rng.randint(0, 1) for _ in range(80)] for _ in range(70)]
+        m_l[5] = list(m_l[6])
+        m = GF2Matrix(m_l)
+        xs = [[rng.randint(0, 1) for _ in range(80)] for _ in range(4)]
+        columns = [list(m.mul_vec(x)) for x in xs]
+        columns.append([1 if i == 5 else 0 for i in range(70)])
+        b = GF2Matrix([list(row) for row in zip(*columns)])
+
+        solutions, basis = m.solve_many(b)
+        self.assertEqual(len(solutions), 5)
+        for column, x in zip(columns[:4], solutions[:4]):
+            self.assertEqual(list(m.mul_vec(list(x))), column)
+        self.assertIsNone(solutions[4])
+        self.assertEqual(len(basis), 80 - m.rank())
+        for v in basis:
+            self.assertFalse(any(m.mul_vec(list(v))))
+
+
+class TestKernel(unittest.TestCase):
+
+    def test_kernel_of_unreduced_matrix(self):
+        m_l = [[1, 1, 0, 1], [1, 0, 1, 1], [0, 1, 1, 0]]
+        m = GF2Matrix(m_l)
+        basis = [list(v) for v in m.kernel()]
+        self.assertEqual(len(basis), 2)
+        self.assertEqual(span(basis, 4), {
+            x for x in itertools.product([0, 1], repeat=4) if not any(m.mul_vec(list(x)))
+        })
+
+    def test_kernel_matches_reduced_input(self):
+        m = GF2Matrix([[1, 0, 1, 0], [0, 1, 1, 0], [0, 0, 0, 1]])
+        self.assertEqual([list(v) for v in m.kernel()], [[1, 1, 1, 0]])
+
+    def test_left_kernel(self):
+        rng = random.Random(21)
+        m_l = [[rng.randint(0, 1) for _ in range(5)] for _ in range(8)]
+        m = GF2Matrix(m_l)
+        basis = m.left_kernel(as_matrix=True)
+        self.assertEqual((basis.nrows(), basis.ncols()), (8 - m.rank(), 8))
+        product = basis @ m
+        self.assertEqual(product.weight(), 0)
+
+    def test_kernel_as_matrix(self):
+        m = GF2Matrix([[1, 1, 1]])
+        basis = m.kernel(as_matrix=True)
+        self.assertEqual([list(r) for r in basis.to_list()], [list(v) for v in m.kernel()])
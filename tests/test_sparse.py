@@ -0,0 +1,96 @@
+import itertools
+import random
+import unittest
+
+from algebraic_immunity_utils import Matrix as GF2Matrix
+from algebraic_immunity_utils import SparseMatrix
+
+from helpers import span
+
+
+def random_sparse(rng, rows, cols, weight):
+    return [rng.sample(range(cols), rng.randint(0, weight)) for _ in range(rows)]
+
+
+class TestSparseMatrix(unittest.TestCase):
+
+    def test_dense_round_trip(self):
+        m_l = [[1, 0, 0, 1], [0, 0, 0, 0], [0, 1, 1, 0]]
+        s = SparseMatrix.from_dense(GF2Matrix(m_l))
+        self.assertEqual(s.to_rows(), [[0, 3], [], [1, 2]])
+        self.assertEqual(s.nnz(), 4)
+        self.assertEqual([list(r) for r in s.to_dense().to_list()], m_l)
+
+    def test_repeated_indices_cancel(self):
+        s = SparseMatrix([[2, 0, 2, 2], [1, 1]], 3)
+        self.assertEqual(s.to_rows(), [[0, 2], []])
+        with self.assertRaises(IndexError):
+            SparseMatrix([[3]], 3)
+
+    def test_rank_matches_dense(self):
+        rng = random.Random(7)
+        for rows, cols, weight in [(10, 12, 3), (40, 30, 4), (60, 80, 2), (30, 30, 20)]:
+            s = SparseMatrix(random_sparse(rng, rows, cols, weight), cols)
+            self.assertEqual(s.rank(), s.to_dense().rank())
+
+    def test_kernel(self):
+        rng = random.Random(8)
+        for rows, cols, weight in [(5, 9, 3), (12, 12, 2), (8, 10, 8)]:
+            s = SparseMatrix(random_sparse(rng, rows, cols, weight), cols)
+            kernel = [list(v) for v in s.kernel()]
+            self.assertEqual(len(kernel), cols - s.rank())
+            for v in kernel:
+                self.assertEqual(list(s.mul_vec(v)), [0] * rows)
+            dense_kernel = [list(v) for v in s.to_dense().kernel()]
+            self.assertEqual(span(kernel, cols), span(dense_kernel, cols))
+
+    def test_solve(self):
+        rng = random.Random(9)
+        for rows, cols, weight in [(6, 8, 3), (9, 6, 2), (7, 7, 7)]:
+            s = SparseMatrix(random_sparse(rng, rows, cols, weight), cols)
+            b = [rng.randint(0, 1) for _ in range(rows)]
+            expected = {
+                x for x in itertools.product([0, 1], repeat=cols)
+                if list(s.mul_vec(list(x))) == b
+            }
+            result = s.solve(b)
+            if not expected:
+                self.assertIsNone(result)
+                continue
+            particular, basis = result
+            solutions = {tuple(p ^ v for p, v in zip(particular, k)) for k in span([list(v) for v in basis], cols)}
+            self.assertEqual(solutions, expected)
+
+
+class TestBlockLanczos(unittest.TestCase):
+
+    def test_kernel(self):
+        rng = random.Random(10)
+        for rows, cols in [(20, 30), (150, 170), (300, 300)]:
+            s = SparseMatrix([rng.sample(range(cols), 5) for _ in range(rows)], cols)
+            kernel = [list(v) for v in s.block_lanczos_kernel(seed=1)]
+            self.assertEqual(len(kernel), min(64, cols - s.rank()))
+            for v in kernel:
+                self.assertEqual(list(s.mul_vec(v)), [0] * rows)
+            self.assertEqual(GF2Matrix(kernel).rank() if kernel else 0, len(kernel))
+
+    def test_seed_is_deterministic(self):
+        rng = random.Random(11)
+        s = SparseMatrix([rng.sample(range(120), 4) for _ in range(100)], 120)
+        self.assertEqual(s.block_lanczos_kernel(seed=5), s.block_lanczos_kernel(seed=5))
+
+    def test_solve(self):
+        rng = random.Random(12)
+        s = SparseMatrix([rng.sample(range(200), 6) for _ in range(180)], 200)
+        x = [rng.randint(0, 1) for _ in range(200)]
+        b = list(s.mul_vec(x))
+        solution = s.block_lanczos_solve(b, seed=3)
+        self.assertEqual(list(s.mul_vec(list(solution))), b)
+
+    def test_solve_inconsistent(self):
+        s = SparseMatrix([[0, 1], [0, 1]], 2)
+        self.assertIsNone(s.block_lanczos_solve([1, 0], seed=0))
+
+
+if __name__ == '__main__':
+    unittest.main()
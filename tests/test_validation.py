@@ -0,0 +1,91 @@
+import unittest
+
+from algebraic_immunity_utils import DimensionError, NonBinaryValueError, OpLog, RestrictedAI, verify
+from algebraic_immunity_utils import Matrix as GF2Matrix
+
+
+class TestValidation(unittest.TestCase):
+
+    def test_hierarchy(self):
+        self.assertTrue(issubclass(DimensionError, ValueError))
+        self.assertTrue(issubclass(NonBinaryValueError, ValueError))
+
+    def test_constructor(self):
+        with self.assertRaises(DimensionError):
+            GF2Matrix([[1, 0], [1]])
+        for value in [2, 256, -1]:
+            with self.assertRaises(NonBinaryValueError):
+                GF2Matrix([[1, value]])
+            m = GF2Matrix([[1, 0]])
+            with self.assertRaises(NonBinaryValueError):
+                m[0, 1] = value
+            self.assertEqual(m, GF2Matrix([[1, 0]]))
+        with self.assertRaises(DimensionError):
+            GF2Matrix.from_int_rows([0b1000], 3)
+        with self.assertRaises(NonBinaryValueError):
+            GF2Matrix.from_int_rows([-1], 3)
+
+    def test_indices(self):
+        m = GF2Matrix([[1, 0], [0, 1]])
+        for call in [lambda: m.get(2, 0), lambda: m.get(0, 2), lambda: m.swap_rows(0, 2),
+                     lambda: m.add_rows(3, 0), lambda: m.get_sub_matrix(1, 3), lambda: m.get_sub_matrix(2, 1),
+                     lambda: m.row_weight(5), lambda: OpLog()[0]]:
+            with self.assertRaises(IndexError):
+                call()
+        self.assertEqual(m.get_sub_matrix(1, 2).nrows(), 1)
+
+    def test_append(self):
+        m = GF2Matrix([[1, 0], [0, 1]])
+        with self.assertRaises(DimensionError):
+            m.append_row([1, 0, 1])
+        with self.assertRaises(DimensionError):
+            m.append_column([1])
+        with self.assertRaises(NonBinaryValueError):
+            m.append_column([1, 3])
+        self.assertEqual((m.nrows(), m.ncols()), (2, 2))
+
+        m = GF2Matrix.zeros(0, 3)
+        with self.assertRaises(DimensionError):
+            m.append_row([1])
+        m = GF2Matrix([])
+        with self.assertRaises(NonBinaryValueError):
+            m.append_row([1, 2])
+        self.assertEqual((m.nrows(), m.ncols()), (0, 0))
+        m.append_row([1, 0])
+        self.assertEqual((m.nrows(), m.ncols()), (1, 2))
+
+    def test_strings(self):
+        with self.assertRaises(NonBinaryValueError):
+            GF2Matrix.compute_vandermonde(['01', 'x1'], ['00'])
+        with self.assertRaises(DimensionError):
+            GF2Matrix.compute_vandermonde(['01', '011'], ['00'])
+        m = GF2Matrix([[1, 1], [1, 0]])
+        with self.assertRaises(DimensionError):
+            m.compute_next(['00', '01'], ['00', '01', '10'], 2, OpLog())
+        with self.assertRaises(DimensionError):
+            m.compute_next(['00', '01', '10'], ['00', '01', '10'], 2, OpLog.from_pairs([(0, 5)]))
+        with self.assertRaises(NonBinaryValueError):
+            m.fill_rows(['0a'], ['00', '01'])
+        with self.assertRaises(DimensionError):
+            verify(['001'], [0, 0, 1], ['000', '001'])
+        with self.assertRaises(NonBinaryValueError):
+            verify(['0-1'], [0, 0, 1], ['000', '001', '010'])
+
+    def test_restricted_ai(self):
+        truth_table = [0, 1, 1, 0, 1, 0, 0, 1]
+        self.assertEqual(RestrictedAI.algebraic_immunity(truth_table, list(range(8)), 3), 1)
+        with self.assertRaises(IndexError):
+            RestrictedAI.algebraic_immunity(truth_table, [0, 1, 8], 3)
+        with self.assertRaises(DimensionError):
+            RestrictedAI.algebraic_immunity(truth_table, [0, 1], 2)
+        for n in [64, 100, 2 ** 32 + 3]:
+            with self.assertRaises(DimensionError):
+                RestrictedAI.algebraic_immunity(truth_table, [0, 1], n)
+            with self.assertRaises(DimensionError):
+                RestrictedAI(truth_table).compute_z([0, 1], n)
+        with self.assertRaises(NonBinaryValueError):
+            RestrictedAI([0, 2])
+
+
+if __name__ == '__main__':
+    unittest.main()